@@ -0,0 +1,190 @@
+//! # Configuration for we_clap on the web
+//!
+//! The [`Config`] is kept per thread.  Set it once with [`set_config`]
+//! before parsing.
+
+use crate::env::EnvSource;
+use crate::query::QueryRules;
+use crate::sanitize::ControlChars;
+use clap::error::ErrorKind;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    static CONFIG: RefCell<Config> = RefCell::new(Config::default());
+}
+
+/// # Options for turning the url into command line arguments
+///
+/// Create with [`Config::default()`] and change the fields you need.
+///
+/// # Example
+/// ``` rust
+/// let config = we_clap::Config {
+///     use_equals_form: false,
+///     ..Default::default()
+/// };
+/// we_clap::set_config(config);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Translate `?key=value` to the single argument `--key=value`.
+    ///
+    /// When `false` two arguments, `--key value`, are used instead.  The
+    /// equals form is unambiguous for clap, a value starting with `-` is
+    /// never mistaken for a flag.
+    ///
+    /// Without the equals form a value like `-x+1` is only taken as a value
+    /// by args with clap's `allow_hyphen_values`, or `allow_negative_numbers`
+    /// for numbers, same as on native.  Positional values from the
+    /// [`positional_key`](Config::positional_key) always need them, and so
+    /// do the values of an arg with several values per occurrence, like
+    /// `num_args = 2`, in either form.  Its repeated keys are grouped into
+    /// one `--key value value` occurrence.
+    ///
+    /// Default `true`.
+    pub use_equals_form: bool,
+
+    /// Query key for positional values.
+    ///
+    /// `?_=file.txt` gives the positional value `file.txt`.  Repeat the key
+    /// for more positionals, `?_=in.txt&_=out.txt`.
+    ///
+    /// Default `"_"`.
+    pub positional_key: String,
+
+    /// Split each positional key value into several positionals.
+    ///
+    /// With `Some('|')` the query `?_=a|b|c` gives the three positionals
+    /// `a b c`.  This can be mixed with repeated keys, every value is split
+    /// on its own and the positionals keep their url order, so
+    /// `?_=a|b&_=c` also gives `a b c`.
+    ///
+    /// Default `None`, values are not split.
+    pub positional_delimiter: Option<char>,
+
+    /// Where [`we_env`](crate::we_env) looks for values on the web, in order.
+    ///
+    /// Default `[Storage, Global, Meta]`.
+    pub env_sources: Vec<EnvSource>,
+
+    /// Report every unexpected arg in the url, not only the first.
+    ///
+    /// Clap stops at the first arg it doesn't know.  With this set the
+    /// short circuit paths, like [`we_parse`](crate::WeParser::we_parse()),
+    /// remove it and parse again until every unexpected arg is found, then
+    /// report them all in one message.
+    ///
+    /// This is an approximation.  Removing an arg can change how the rest
+    /// are paired with values, and with `use_equals_form: false` the value
+    /// of an unexpected key may be reported as well.
+    ///
+    /// Default `false`.
+    pub report_all_unexpected: bool,
+
+    /// Write set boolean flags as `verbose=true` in a query made by
+    /// [`we_to_query`](crate::we_to_query), instead of the bare `--verbose`.
+    ///
+    /// Only flags that accept a value, for example with
+    /// `num_args(0..=1)` and `default_missing_value("true")`, parse the
+    /// value form back.  Plain clap flags reject it.
+    ///
+    /// Default `false`, the bare form.
+    pub flag_values: bool,
+
+    /// What to do with control characters, like NUL or escape, in the url.
+    ///
+    /// Tab, line feed and carriage return are always kept.
+    ///
+    /// Default [`ControlChars::Strip`], they are removed.
+    pub control_chars: ControlChars,
+
+    /// Process exit code for each kind of error.
+    ///
+    /// Lets scripts tell a missing arg from an invalid value.  Kinds not in
+    /// the map exit with clap's code, `0` for help and version and `2` for
+    /// errors.  On the web the code only matters under WASI or a Node
+    /// harness.  A [`WeParser::we_exit_code()`](crate::WeParser::we_exit_code())
+    /// of your own takes precedence.
+    ///
+    /// Default empty, clap's codes.
+    pub exit_codes: HashMap<ErrorKind, i32>,
+
+    /// Query keys that give a count, for `ArgAction::Count` flags.
+    ///
+    /// With `vec!["v".into()]` the query `?v=3` gives `-v -v -v`, like
+    /// `-vvv` on native, and `?verbose=2` gives `--verbose --verbose` for
+    /// `"verbose"`.  The count is a number from `0` to `255`, clap's most
+    /// for a count.  Other values are passed on as usual, for clap to
+    /// report.  Repeating a bare key, `?v&v&v`, counts without this.
+    ///
+    /// Default empty, no key is expanded.
+    pub count_params: Vec<String>,
+
+    /// Reject a url path that doesn't start with a subcommand, with
+    /// [`ArgSource::Path`](crate::ArgSource::Path).
+    ///
+    /// For routing by path, `/mytool/convert/input.txt` runs `convert`.
+    /// With this set a first path segment that isn't a subcommand of the
+    /// command, by name or alias, fails with
+    /// [`WeError::UnknownSubcommand`](crate::WeError::UnknownSubcommand)
+    /// instead of being given to clap as a positional.  A path with no
+    /// segments is not checked.
+    ///
+    /// Default `false`, the segment is a positional.
+    pub strict_path_subcommands: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            use_equals_form: true,
+            positional_key: "_".into(),
+            positional_delimiter: None,
+            env_sources: vec![EnvSource::Storage, EnvSource::Global, EnvSource::Meta],
+            report_all_unexpected: false,
+            flag_values: false,
+            control_chars: ControlChars::Strip,
+            exit_codes: HashMap::new(),
+            count_params: Vec::new(),
+            strict_path_subcommands: false,
+        }
+    }
+}
+
+impl Config {
+    /// The translation rules, borrowed from this config.
+    #[must_use]
+    pub fn query_rules(&self) -> QueryRules<'_> {
+        QueryRules {
+            use_equals_form: self.use_equals_form,
+            positional_key: &self.positional_key,
+            positional_delimiter: self.positional_delimiter,
+        }
+    }
+}
+
+/// # Set the we_clap configuration
+///
+/// Replaces the configuration for the current thread.
+///
+/// # Example
+/// ``` rust
+/// we_clap::set_config(we_clap::Config::default());
+/// ```
+pub fn set_config(config: Config) {
+    CONFIG.with(|current| *current.borrow_mut() = config);
+}
+
+/// # Get the we_clap configuration
+///
+/// A copy of the configuration for the current thread.
+///
+/// # Example
+/// ``` rust
+/// assert!(we_clap::config().use_equals_form);
+/// ```
+#[must_use]
+pub fn config() -> Config {
+    CONFIG.with(|current| current.borrow().clone())
+}
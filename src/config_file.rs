@@ -0,0 +1,527 @@
+//! # Args from a config file
+//!
+//! [`we_parse_with_config`](crate::WeParser::we_parse_with_config()) reads
+//! the file named by a `--config` arg, a path on native or a url fetched on
+//! the web, and layers its values under the explicit args.
+//!
+//! Only flat config files are read, without serde:
+//!
+//! * JSON, one object of strings, numbers, booleans, `null` and arrays of
+//!   those, `{"name": "Jo", "level": 3, "tag": ["a", "b"]}`.
+//! * TOML, top level `key = value` pairs with the same values,
+//!   `name = "Jo"`, and `#` comments.  Tables are not supported.
+
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgAction, Command};
+use std::fmt;
+use std::future::Future;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// The keys and values of a config file, in file order.
+pub(crate) type Entries = Vec<(String, Vec<Value>)>;
+
+/// A config value.  Strings and numbers are text, only a bare `true` or
+/// `false` is a boolean, `"true"` is text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Value {
+    Text(String),
+    Bool(bool),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(text) => f.write_str(text),
+            Self::Bool(set) => write!(f, "{set}"),
+        }
+    }
+}
+
+/// Parse a flat JSON object, or flat TOML.
+pub(crate) fn parse_config(text: &str) -> Result<Entries, String> {
+    let mut reader = Reader {
+        chars: text.chars().peekable(),
+    };
+    if text.trim_start().starts_with('{') {
+        reader.json()
+    } else {
+        reader.toml()
+    }
+}
+
+/// Parse a flat JSON object.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "json-args")))]
+pub(crate) fn parse_json(text: &str) -> Result<Entries, String> {
+    Reader {
+        chars: text.chars().peekable(),
+    }
+    .json()
+}
+
+/// A cursor over the config text.
+struct Reader<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Reader<'_> {
+    /// Skip spaces, and with `lines` newlines and `#` comments too.
+    fn skip(&mut self, lines: bool) {
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                ' ' | '\t' => {}
+                '\n' | '\r' if lines => {}
+                '#' if lines => {
+                    while self.chars.next_if(|&c| c != '\n').is_some() {}
+                    continue;
+                }
+                _ => return,
+            }
+            self.chars.next();
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.chars.next_if_eq(&c).is_some()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        match self.chars.next() {
+            Some(found) if found == c => Ok(()),
+            Some(found) => Err(format!("expected `{c}`, found `{found}`")),
+            None => Err(format!("expected `{c}`, found the end")),
+        }
+    }
+
+    /// The rest of a string after its opening quote.  A `'` string is
+    /// literal, a `"` string has backslash escapes.
+    fn string(&mut self, quote: char) -> Result<String, String> {
+        let mut text = String::new();
+        loop {
+            match self.chars.next() {
+                None | Some('\n') => return Err("unclosed string".into()),
+                Some(c) if c == quote => return Ok(text),
+                Some('\\') if quote == '"' => text.push(self.escape()?),
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, String> {
+        Ok(match self.chars.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('u') => {
+                let hex: String = (0..4).filter_map(|_| self.chars.next()).collect();
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("bad escape `\\u{hex}`"))?
+            }
+            Some(c @ ('"' | '\\' | '/')) => c,
+            Some(c) => return Err(format!("bad escape `\\{c}`")),
+            None => return Err("unclosed string".into()),
+        })
+    }
+
+    /// One value, `None` for `null`.
+    fn scalar(&mut self) -> Result<Option<Value>, String> {
+        match self.chars.peek() {
+            Some(&quote @ ('"' | '\'')) => {
+                self.chars.next();
+                self.string(quote).map(|text| Some(Value::Text(text)))
+            }
+            Some('{' | '[') => Err("nested values are not supported".into()),
+            _ => {
+                let mut bare = String::new();
+                while let Some(c) = self.chars.next_if(|c| !",]}#\r\n".contains(*c)) {
+                    bare.push(c);
+                }
+                match bare.trim() {
+                    "" => Err("missing value".into()),
+                    "null" => Ok(None),
+                    "true" => Ok(Some(Value::Bool(true))),
+                    "false" => Ok(Some(Value::Bool(false))),
+                    bare => Ok(Some(Value::Text(bare.to_string()))),
+                }
+            }
+        }
+    }
+
+    /// A value or an array of them.
+    fn values(&mut self) -> Result<Vec<Value>, String> {
+        if !self.eat('[') {
+            return Ok(self.scalar()?.into_iter().collect());
+        }
+        let mut values = Vec::new();
+        loop {
+            self.skip(true);
+            if self.eat(']') {
+                return Ok(values);
+            }
+            values.extend(self.scalar()?);
+            self.skip(true);
+            if !self.eat(',') {
+                self.expect(']')?;
+                return Ok(values);
+            }
+        }
+    }
+
+    fn json(&mut self) -> Result<Entries, String> {
+        let mut entries = Entries::new();
+        self.skip_lines();
+        self.expect('{')?;
+        loop {
+            self.skip_lines();
+            if self.eat('}') {
+                break;
+            }
+            self.expect('"')?;
+            let key = self.string('"')?;
+            self.skip_lines();
+            self.expect(':')?;
+            self.skip_lines();
+            let values = self.values()?;
+            if !values.is_empty() {
+                entries.push((key, values));
+            }
+            self.skip_lines();
+            if !self.eat(',') {
+                self.expect('}')?;
+                break;
+            }
+        }
+        self.skip_lines();
+        match self.chars.next() {
+            None => Ok(entries),
+            Some(c) => Err(format!("unexpected `{c}` after the object")),
+        }
+    }
+
+    fn toml(&mut self) -> Result<Entries, String> {
+        let mut entries = Entries::new();
+        loop {
+            self.skip(true);
+            let key = match self.chars.peek() {
+                None => return Ok(entries),
+                Some('[') => return Err("tables are not supported".into()),
+                Some(&quote @ ('"' | '\'')) => {
+                    self.chars.next();
+                    self.string(quote)?
+                }
+                Some(_) => {
+                    let mut key = String::new();
+                    while let Some(c) = self.chars.next_if(|c| !"= \t\r\n".contains(*c)) {
+                        key.push(c);
+                    }
+                    key
+                }
+            };
+            self.skip(false);
+            self.expect('=')?;
+            self.skip(false);
+            let values = self.values()?;
+            if !values.is_empty() {
+                entries.push((key, values));
+            }
+            self.skip(false);
+            match self.chars.peek() {
+                None | Some('\n' | '\r' | '#') => {}
+                Some(c) => return Err(format!("unexpected `{c}` after a value")),
+            }
+        }
+    }
+
+    /// Skip whitespace, including newlines, in JSON.
+    fn skip_lines(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+}
+
+/// Add the config values of args that are not in the args.
+///
+/// A key names an arg by its long flag, or by its id.  Values go after the
+/// program name as `--long=value`, one for each value of an array, or the
+/// flag alone for a flag set by `true`, or by `false` for a
+/// [`SetFalse`](ArgAction::SetFalse) flag, so the explicit args still win.
+/// A [`Count`](ArgAction::Count) flag is repeated, `verbose = 2` gives
+/// `--verbose --verbose`.  Positionals and args of subcommands are skipped.
+///
+/// Fails on a count that isn't a number.
+pub(crate) fn layer(
+    command: &Command,
+    mut args: Vec<String>,
+    entries: &Entries,
+) -> Result<Vec<String>, String> {
+    let Ok(matches) = command
+        .clone()
+        .ignore_errors(true)
+        .try_get_matches_from(&args)
+    else {
+        return Ok(args);
+    };
+    let mut config_args = Vec::new();
+    for arg in command.get_arguments() {
+        if matches!(
+            matches.value_source(arg.get_id().as_str()),
+            Some(ValueSource::CommandLine)
+        ) {
+            continue;
+        }
+        let flag = match (arg.get_long(), arg.get_short()) {
+            (Some(long), _) => format!("--{long}"),
+            (None, Some(short)) => format!("-{short}"),
+            (None, None) => continue,
+        };
+        let Some((key, values)) = entries
+            .iter()
+            .find(|(key, _)| Some(key.as_str()) == arg.get_long() || key == arg.get_id().as_str())
+        else {
+            continue;
+        };
+        match arg.get_action() {
+            ArgAction::SetTrue if values.contains(&Value::Bool(true)) => config_args.push(flag),
+            ArgAction::SetFalse if values.contains(&Value::Bool(false)) => config_args.push(flag),
+            ArgAction::Set | ArgAction::Append => {
+                config_args.extend(values.iter().map(|value| format!("{flag}={value}")));
+            }
+            ArgAction::Count => {
+                let count = match values.as_slice() {
+                    [Value::Bool(set)] => Some(u8::from(*set)),
+                    [Value::Text(count)] => count.parse::<u8>().ok(),
+                    _ => None,
+                }
+                .ok_or_else(|| format!("`{key}` needs a count, like `{key} = 2`"))?;
+                config_args.extend(vec![flag; usize::from(count)]);
+            }
+            _ => {}
+        }
+    }
+    let at = args.len().min(1);
+    args.splice(at..at, config_args);
+    Ok(args)
+}
+
+/// The args with the config file named by `config_arg` layered under
+/// them, read with `read`.  The args as they are without a config arg.
+///
+/// The config arg is found ignoring errors, a required arg may still come
+/// from the config.
+pub(crate) async fn with_config<R, F>(
+    command: &Command,
+    args: Vec<String>,
+    config_arg: &str,
+    read: R,
+) -> clap::error::Result<Vec<String>>
+where
+    R: FnOnce(String) -> F,
+    F: Future<Output = Result<String, String>>,
+{
+    let Ok(matches) = command
+        .clone()
+        .ignore_errors(true)
+        .try_get_matches_from(&args)
+    else {
+        return Ok(args);
+    };
+    let location = matches
+        .get_raw(config_arg)
+        .and_then(|mut values| values.next())
+        .map(|value| value.to_string_lossy().into_owned());
+    let Some(location) = location else {
+        return Ok(args);
+    };
+    let text = read(location.clone()).await.map_err(|reason| {
+        command.clone().error(
+            ErrorKind::Io,
+            format!("can't read config `{location}`: {reason}"),
+        )
+    })?;
+    let entries = parse_config(&text).map_err(|reason| {
+        command
+            .clone()
+            .error(ErrorKind::Io, format!("bad config `{location}`: {reason}"))
+    })?;
+    layer(command, args, &entries).map_err(|reason| {
+        command
+            .clone()
+            .error(ErrorKind::Io, format!("bad config `{location}`: {reason}"))
+    })
+}
+
+/// The text of a config file, from a path on native or a url on the web.
+pub(crate) async fn read_config(location: String) -> Result<String, String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::fs::read_to_string(&location).map_err(|err| err.to_string())
+    }
+    #[cfg(all(target_arch = "wasm32", feature = "web-config-fetch"))]
+    {
+        fetch_text(&location).await
+    }
+    #[cfg(all(target_arch = "wasm32", not(feature = "web-config-fetch")))]
+    {
+        Err(format!(
+            "fetching `{location}` needs the \"web-config-fetch\" feature"
+        ))
+    }
+}
+
+/// Fetch the text at the url.
+#[cfg(all(target_arch = "wasm32", feature = "web-config-fetch"))]
+async fn fetch_text(url: &str) -> Result<String, String> {
+    use crate::future::PromiseFuture;
+    use wasm_bindgen::JsCast;
+
+    let js_text =
+        |value: wasm_bindgen::JsValue| value.as_string().unwrap_or_else(|| format!("{value:?}"));
+    let window = web_sys::window().ok_or("no window")?;
+    let response: web_sys::Response = PromiseFuture::new(&window.fetch_with_str(url))
+        .await
+        .map_err(js_text)?
+        .dyn_into()
+        .map_err(js_text)?;
+    if !response.ok() {
+        return Err(format!("status {}", response.status()));
+    }
+    let text = PromiseFuture::new(&response.text().map_err(js_text)?)
+        .await
+        .map_err(js_text)?;
+    text.as_string().ok_or_else(|| "not text".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::future::block_on;
+    use clap::Arg;
+
+    fn entry(key: &str, values: &[&str]) -> (String, Vec<Value>) {
+        (
+            key.into(),
+            values
+                .iter()
+                .map(|value| Value::Text(value.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn config_formats() {
+        let json = r#"{ "name": "Jo \"J\"", "level": 3, "debug": true,
+            "tag": ["a", "b"], "gone": null }"#;
+        let toml = "# app config\nname = 'Jo \"J\"'\nlevel = 3 # three\ndebug = true\ntag = [\"a\", \"b\"]\n";
+        let expected = vec![
+            entry("name", &["Jo \"J\""]),
+            entry("level", &["3"]),
+            ("debug".into(), vec![Value::Bool(true)]),
+            entry("tag", &["a", "b"]),
+        ];
+        assert_eq!(parse_config(json), Ok(expected.clone()));
+        assert_eq!(parse_config(toml), Ok(expected));
+        assert!(parse_config("[server]\nport = 1").is_err());
+        assert!(parse_config(r#"{"a": {"b": 1}}"#).is_err());
+        assert!(parse_config("name = \"open").is_err());
+        assert_eq!(
+            parse_config(r#"{"debug": "true"}"#),
+            Ok(vec![entry("debug", &["true"])])
+        );
+    }
+
+    #[test]
+    fn config_under_args() {
+        let command = Command::new("app")
+            .arg(Arg::new("config").long("config"))
+            .arg(Arg::new("name").long("name"))
+            .arg(Arg::new("tag").long("tag").action(ArgAction::Append))
+            .arg(Arg::new("debug").long("debug").action(ArgAction::SetTrue));
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let read = |location: String| async move {
+            match location.as_str() {
+                "app.toml" => Ok("name = \"Jo\"\ntag = [\"a\", \"b\"]\ndebug = true".into()),
+                _ => Err("not found".into()),
+            }
+        };
+        let layered = block_on(with_config(
+            &command,
+            args(&["app", "--config=app.toml", "--name=Al"]),
+            "config",
+            read,
+        ));
+        assert_eq!(
+            layered.unwrap(),
+            [
+                "app",
+                "--tag=a",
+                "--tag=b",
+                "--debug",
+                "--config=app.toml",
+                "--name=Al"
+            ]
+        );
+        let unread = block_on(with_config(&command, args(&["app"]), "config", read));
+        assert_eq!(unread.unwrap(), ["app"]);
+        let missing = block_on(with_config(
+            &command,
+            args(&["app", "--config=gone.toml"]),
+            "config",
+            read,
+        ));
+        assert_eq!(missing.unwrap_err().kind(), ErrorKind::Io);
+    }
+
+    #[test]
+    fn config_flag_actions() {
+        let command = Command::new("app")
+            .arg(Arg::new("color").long("color").action(ArgAction::SetTrue))
+            .arg(
+                Arg::new("plain")
+                    .long("no-color")
+                    .action(ArgAction::SetFalse),
+            )
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::Count),
+            );
+        let args = vec!["app".to_string()];
+        let layer = |text: &str| layer(&command, args.clone(), &parse_config(text).unwrap());
+
+        assert_eq!(layer("no-color = true").unwrap(), ["app"]);
+        assert_eq!(layer("no-color = false").unwrap(), ["app", "--no-color"]);
+        assert_eq!(layer("color = false").unwrap(), ["app"]);
+        let matches = command
+            .clone()
+            .try_get_matches_from(layer("plain = false\ncolor = true").unwrap())
+            .unwrap();
+        assert!(!matches.get_flag("plain"));
+        assert!(matches.get_flag("color"));
+
+        let layered = layer("verbose = 2").unwrap();
+        assert_eq!(layered, ["app", "--verbose", "--verbose"]);
+        let matches = command.clone().try_get_matches_from(layered).unwrap();
+        assert_eq!(matches.get_count("verbose"), 2);
+        assert_eq!(layer("verbose = true").unwrap(), ["app", "--verbose"]);
+        assert_eq!(
+            layer("verbose = \"lots\"").unwrap_err(),
+            "`verbose` needs a count, like `verbose = 2`"
+        );
+    }
+
+    #[test]
+    fn config_gives_required() {
+        let command = Command::new("app")
+            .arg(Arg::new("config").long("config"))
+            .arg(Arg::new("name").long("name").required(true));
+        let read = |_: String| async { Ok("name = \"Jo\"".to_string()) };
+        let args = vec!["app".to_string(), "--config=app.toml".to_string()];
+        let layered = block_on(with_config(&command, args, "config", read)).unwrap();
+        assert_eq!(layered, ["app", "--name=Jo", "--config=app.toml"]);
+        let matches = command.try_get_matches_from(layered).unwrap();
+        assert_eq!(matches.get_one::<String>("name").unwrap(), "Jo");
+    }
+}
@@ -0,0 +1,79 @@
+//! # The args in the browser console
+//!
+//! With the "web-debug" feature each web parse logs the raw query string
+//! and the args given to clap with `console.debug`, so a link that doesn't
+//! parse can be checked in devtools.  Off without the feature, so
+//! production pages stay quiet.
+
+use std::cell::Cell;
+
+thread_local! {
+    static DEBUG: Cell<bool> = const { Cell::new(cfg!(feature = "web-debug")) };
+}
+
+/// # Log the args of web parses to the console
+///
+/// On with the "web-debug" feature, turn it off for a page that should be
+/// quiet, or back on.  Each web parse then logs
+///
+/// ``` text
+/// we_clap: search = "?verbose&name=Jo"
+/// we_clap: args = ["app", "--verbose", "--name=Jo"]
+/// ```
+///
+/// with `console.debug`, shown at the devtools "Verbose" level.  Without
+/// the feature and on native nothing is logged.
+///
+/// # Example
+/// ``` rust
+/// we_clap::set_debug(false);
+/// ```
+pub fn set_debug(on: bool) {
+    DEBUG.with(|debug| debug.set(on));
+}
+
+/// The lines to log for a parse.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-debug")))]
+fn debug_lines(search: &str, args: &[String]) -> [String; 2] {
+    [
+        format!("we_clap: search = {search:?}"),
+        format!("we_clap: args = {args:?}"),
+    ]
+}
+
+/// Log the args given to clap, when debugging.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn log_args(args: &[String]) {
+    #[cfg(feature = "web-debug")]
+    if DEBUG.with(Cell::get) {
+        let search = web_sys::window()
+            .and_then(|window| window.location().search().ok())
+            .unwrap_or_default();
+        for line in debug_lines(&search, args) {
+            web_sys::console::debug_1(&line.into());
+        }
+    }
+    #[cfg(not(feature = "web-debug"))]
+    let _ = args;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_output() {
+        let args = ["app".to_string(), "--name=Jo Jo".to_string()];
+        assert_eq!(
+            debug_lines("?name=Jo+Jo", &args),
+            [
+                r#"we_clap: search = "?name=Jo+Jo""#,
+                r#"we_clap: args = ["app", "--name=Jo Jo"]"#,
+            ]
+        );
+        set_debug(true);
+        assert!(DEBUG.with(Cell::get));
+        set_debug(false);
+        assert!(!DEBUG.with(Cell::get));
+    }
+}
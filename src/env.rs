@@ -0,0 +1,243 @@
+//! # Portable environment lookup
+//!
+//! Environment variables don't exist on the web.  [`we_env`] reads them on
+//! native and reads page provided values on the web.
+//!
+//! With the "web-env" feature an arg declared with `env("MY_VAR")` reads
+//! `MY_VAR` from the same web sources, when the url doesn't give the arg.
+//! The lookup order is the url arg, then the
+//! [`Config::env_sources`](crate::Config::env_sources), then the clap
+//! default.
+
+#[cfg(all(feature = "web-env", any(test, target_arch = "wasm32")))]
+use clap::{parser::ValueSource, ArgAction, Command};
+
+/// # A place to find a config value on the web
+///
+/// Listed in [`Config::env_sources`](crate::Config::env_sources), in the
+/// order they are searched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvSource {
+    /// The [`localStorage`] item with the key.
+    ///
+    /// [`localStorage`]: https://developer.mozilla.org/en-US/docs/Web/API/Window/localStorage
+    Storage,
+    /// A string property of the JavaScript global object, `globalThis[key]`.
+    Global,
+    /// The content of a `<meta name="key" content="value">` element.
+    Meta,
+}
+
+/// # Get a config value on native or the web
+///
+/// On native this is [`std::env::var`].
+///
+/// On the web the sources in [`Config::env_sources`](crate::Config::env_sources)
+/// are searched in order and the first value found is returned.  By default
+/// that is [`localStorage`](EnvSource::Storage), then a
+/// [global](EnvSource::Global), then a [meta element](EnvSource::Meta).
+/// Needs the "web-env" feature, without it nothing is found on the web.
+///
+/// # Example
+/// ``` rust
+/// let level = we_clap::we_env("LOG_LEVEL").unwrap_or_else(|| "warn".into());
+/// ```
+#[must_use]
+pub fn we_env(key: &str) -> Option<String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::env::var(key).ok()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        lookup(key, &crate::config().env_sources, web_lookup)
+    }
+}
+
+/// First value found for the key in the sources.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn lookup(
+    key: &str,
+    sources: &[EnvSource],
+    get: impl Fn(EnvSource, &str) -> Option<String>,
+) -> Option<String> {
+    sources.iter().find_map(|source| get(*source, key))
+}
+
+/// Read one web source.
+#[cfg(all(target_arch = "wasm32", feature = "web-env"))]
+fn web_lookup(source: EnvSource, key: &str) -> Option<String> {
+    let window = web_sys::window()?;
+    match source {
+        EnvSource::Storage => window.local_storage().ok()??.get_item(key).ok()?,
+        EnvSource::Global => js_sys::Reflect::get(&js_sys::global(), &key.into())
+            .ok()?
+            .as_string(),
+        EnvSource::Meta => window
+            .document()?
+            .query_selector(&format!("meta[name=\"{key}\"]"))
+            .ok()??
+            .get_attribute("content"),
+    }
+}
+
+/// Add the web values of `env` args that are not in the args.
+///
+/// Values from `lookup` go after the program name as `--long=value`, or
+/// the flag alone for a flag set by a true value, so the url still wins and
+/// they win over the clap default.  The args of the used subcommand are
+/// added at the end.  Positionals and args without a long or short flag are
+/// skipped.
+#[cfg(all(feature = "web-env", any(test, target_arch = "wasm32")))]
+pub(crate) fn env_fallback(
+    command: &Command,
+    mut args: Vec<String>,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Vec<String> {
+    let Ok(matches) = command
+        .clone()
+        .ignore_errors(true)
+        .try_get_matches_from(&args)
+    else {
+        return args;
+    };
+    let top = env_args(command, &matches, &lookup);
+    let mut leaf = Vec::new();
+    let (mut command, mut matches) = (command, &matches);
+    while let Some((name, sub_matches)) = matches.subcommand() {
+        let Some(subcommand) = command.find_subcommand(name) else {
+            break;
+        };
+        (command, matches) = (subcommand, sub_matches);
+        leaf = env_args(command, matches, &lookup);
+    }
+    let at = args.len().min(1);
+    args.splice(at..at, top);
+    args.extend(leaf);
+    args
+}
+
+/// The args for the `env` values of one command's args missing in the
+/// matches.
+#[cfg(all(feature = "web-env", any(test, target_arch = "wasm32")))]
+fn env_args(
+    command: &Command,
+    matches: &clap::ArgMatches,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Vec<String> {
+    command
+        .get_arguments()
+        .filter(|arg| {
+            !matches!(
+                matches.value_source(arg.get_id().as_str()),
+                Some(ValueSource::CommandLine)
+            )
+        })
+        .filter_map(|arg| {
+            let name = arg.get_env()?.to_str()?;
+            let flag = match (arg.get_long(), arg.get_short()) {
+                (Some(long), _) => format!("--{long}"),
+                (None, Some(short)) => format!("-{short}"),
+                (None, None) => return None,
+            };
+            let value = lookup(name)?;
+            match arg.get_action() {
+                ArgAction::SetTrue | ArgAction::SetFalse => {
+                    let falsey = ["", "0", "n", "no", "f", "false", "off"];
+                    (!falsey.contains(&value.trim().to_lowercase().as_str())).then_some(flag)
+                }
+                ArgAction::Set | ArgAction::Append => Some(format!("{flag}={value}")),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Without the web-env feature there are no web sources.
+#[cfg(all(target_arch = "wasm32", not(feature = "web-env")))]
+fn web_lookup(_source: EnvSource, _key: &str) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_env() {
+        std::env::set_var("WE_CLAP_TEST_ENV", "native");
+        assert_eq!(we_env("WE_CLAP_TEST_ENV").as_deref(), Some("native"));
+        assert_eq!(we_env("WE_CLAP_TEST_ENV_MISSING"), None);
+    }
+
+    #[cfg(feature = "web-env")]
+    #[test]
+    fn env_args_from_web() {
+        use clap::{Arg, ArgAction, Command};
+
+        let command = Command::new("app")
+            .arg(
+                Arg::new("theme")
+                    .long("theme")
+                    .env("THEME")
+                    .default_value("light"),
+            )
+            .arg(Arg::new("level").long("level").env("LEVEL"))
+            .arg(
+                Arg::new("debug")
+                    .long("debug")
+                    .env("DEBUG")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("quiet")
+                    .long("quiet")
+                    .env("QUIET")
+                    .action(ArgAction::SetTrue),
+            )
+            .subcommand(Command::new("run").arg(Arg::new("speed").long("speed").env("SPEED")));
+        let storage = |key: &str| match key {
+            "THEME" => Some("dark".to_string()),
+            "LEVEL" => Some("3".to_string()),
+            "DEBUG" => Some("yes".to_string()),
+            "QUIET" => Some("off".to_string()),
+            "SPEED" => Some("9".to_string()),
+            _ => None,
+        };
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect();
+
+        assert_eq!(
+            env_fallback(&command, args(&["app", "--level=1", "run"]), storage),
+            [
+                "app",
+                "--theme=dark",
+                "--debug",
+                "--level=1",
+                "run",
+                "--speed=9"
+            ]
+        );
+        assert_eq!(
+            env_fallback(&command, args(&["app", "--theme=blue"]), |_| None),
+            ["app", "--theme=blue"]
+        );
+    }
+
+    #[test]
+    fn web_source_order() {
+        let page = |source: EnvSource, key: &str| match (source, key) {
+            (EnvSource::Global, "THEME") => Some("global".to_string()),
+            (EnvSource::Meta, "THEME" | "LANG") => Some("meta".to_string()),
+            _ => None,
+        };
+        let sources = crate::Config::default().env_sources;
+
+        assert_eq!(lookup("THEME", &sources, page).as_deref(), Some("global"));
+        assert_eq!(lookup("LANG", &sources, page).as_deref(), Some("meta"));
+        assert_eq!(lookup("NONE", &sources, page), None);
+        assert_eq!(
+            lookup("THEME", &[EnvSource::Meta], page).as_deref(),
+            Some("meta")
+        );
+    }
+}
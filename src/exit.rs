@@ -0,0 +1,233 @@
+//! # Exit on the web
+//!
+//! When help, version or an error short circuits parsing on the web, the
+//! message is reported and the process exits, like clap does on native.
+//! The exit code can be chosen per [`ErrorKind`], see
+//! [`Config::exit_codes`](crate::Config::exit_codes) and
+//! [`WeParser::we_exit_code()`](crate::WeParser::we_exit_code()).
+
+use clap::error::{Error, ErrorKind};
+use std::cell::RefCell;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write;
+
+/// A registered pre-exit hook.
+type PreExitHook = Box<dyn Fn(&str, ErrorKind)>;
+
+thread_local! {
+    static PRE_EXIT: RefCell<Option<PreExitHook>> = RefCell::new(None);
+    static BEFORE_EXIT: RefCell<Option<Box<dyn FnOnce()>>> = RefCell::new(None);
+}
+
+/// # Run a hook right before exiting
+///
+/// When help, version or an error short circuits parsing the message is
+/// shown, in the console or popup alert on the web and on stdout or
+/// stderr on native, then the hook is called with the message and its
+/// [`ErrorKind`], then the process exits.  Use it to send a crash report
+/// about a broken link.
+///
+/// Setting a new hook replaces the previous one.  Only exits of we_clap
+/// parses run it, not those of clap's own `parse` or `get_matches`.
+///
+/// # Example
+/// ``` rust
+/// use clap::error::ErrorKind;
+///
+/// we_clap::we_set_pre_exit_hook(|msg, kind| {
+///     if !matches!(kind, ErrorKind::DisplayHelp | ErrorKind::DisplayVersion) {
+///         // send msg to your logging endpoint
+///     }
+/// });
+/// ```
+pub fn we_set_pre_exit_hook(hook: impl Fn(&str, ErrorKind) + 'static) {
+    PRE_EXIT.with(|pre_exit| *pre_exit.borrow_mut() = Some(Box::new(hook)));
+}
+
+/// # Run cleanup right before exiting
+///
+/// When help, version or an error short circuits parsing, `cleanup` is
+/// called once, right before the process exits, after the message is
+/// shown.  A last chance to flush analytics or save the state of the page.
+/// Any parse that may exit runs it, like
+/// [`we_parse`](crate::WeParser::we_parse()),
+/// [`we_get_matches`](crate::WeCommand::we_get_matches()) and
+/// [`we_get_matches_mut`](crate::WeCommand::we_get_matches_mut()).
+///
+/// Works the same on native.  Only exits of we_clap parses run it, not
+/// those of clap's own `parse` or `get_matches`.  Setting a new cleanup
+/// replaces the previous one.  Runs after the
+/// [pre-exit hook](we_set_pre_exit_hook).
+///
+/// # Example
+/// ``` rust
+/// we_clap::set_before_exit(|| {
+///     // save the page state
+/// });
+/// ```
+pub fn set_before_exit(cleanup: impl FnOnce() + 'static) {
+    BEFORE_EXIT.with(|before_exit| *before_exit.borrow_mut() = Some(Box::new(cleanup)));
+}
+
+/// Run the cleanup set with [`set_before_exit`], once.
+fn before_exit() {
+    let cleanup = BEFORE_EXIT.with(|before_exit| before_exit.borrow_mut().take());
+    if let Some(cleanup) = cleanup {
+        cleanup();
+    }
+}
+
+/// Report a short circuiting clap error and run the pre-exit hook.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn prepare(err: &Error) {
+    crate::output::batched(|| {
+        crate::output::report(err);
+        pre_exit(err);
+    });
+}
+
+/// Run the pre-exit hook with the error.
+fn pre_exit(err: &Error) {
+    PRE_EXIT.with(|pre_exit| {
+        if let Some(hook) = pre_exit.borrow().as_ref() {
+            hook(&format!("{err}"), err.kind());
+        }
+    });
+}
+
+/// # The exit code for a kind of error
+///
+/// The code from [`Config::exit_codes`](crate::Config::exit_codes), or
+/// clap's own: `0` for help and version, `2` for errors.  The default of
+/// [`WeParser::we_exit_code()`](crate::WeParser::we_exit_code()).
+///
+/// # Example
+/// ``` rust
+/// use clap::error::ErrorKind;
+///
+/// assert_eq!(we_clap::we_exit_code(ErrorKind::DisplayHelp), 0);
+/// assert_eq!(we_clap::we_exit_code(ErrorKind::InvalidValue), 2);
+/// ```
+#[must_use]
+pub fn we_exit_code(kind: ErrorKind) -> i32 {
+    crate::config()
+        .exit_codes
+        .get(&kind)
+        .copied()
+        .unwrap_or_else(|| clap_exit_code(kind))
+}
+
+/// The code clap exits with.
+fn clap_exit_code(kind: ErrorKind) -> i32 {
+    match kind {
+        ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => 0,
+        _ => 2,
+    }
+}
+
+/// Print a short circuiting clap error on native, then exit with the
+/// configured code for its kind, or clap's own.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn native_exit(err: &Error) -> ! {
+    native_exit_with(err, we_exit_code(err.kind()))
+}
+
+/// Print a short circuiting clap error on native, then exit with the code.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn native_exit_with(err: &Error, code: i32) -> ! {
+    let _ = err.print();
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+    pre_exit(err);
+    before_exit();
+    std::process::exit(code);
+}
+
+/// Report a short circuiting clap error on the web, then exit with the
+/// configured code for its kind, or clap's own.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn web_exit(err: &Error) -> ! {
+    web_exit_with(err, we_exit_code(err.kind()))
+}
+
+/// Report a short circuiting clap error on the web, then exit with the
+/// code.  It only matters under WASI or a Node harness.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn web_exit_with(err: &Error, code: i32) -> ! {
+    prepare(err);
+    before_exit();
+    std::process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, Command};
+    use std::rc::Rc;
+
+    #[test]
+    fn hook_gets_message_and_kind() {
+        let seen = Rc::new(RefCell::new(None));
+        let sink = Rc::clone(&seen);
+        we_set_pre_exit_hook(move |msg, kind| {
+            *sink.borrow_mut() = Some((msg.to_string(), kind));
+        });
+
+        let err = Command::new("app")
+            .arg(
+                Arg::new("count")
+                    .long("count")
+                    .value_parser(clap::value_parser!(u8)),
+            )
+            .try_get_matches_from(["app", "--count=many"])
+            .unwrap_err();
+        prepare(&err);
+
+        let (msg, kind) = seen.borrow_mut().take().unwrap();
+        assert_eq!(kind, ErrorKind::ValueValidation);
+        assert!(msg.contains("many"));
+    }
+
+    #[test]
+    fn cleanup_runs_once() {
+        let runs = Rc::new(RefCell::new(0));
+        let count = Rc::clone(&runs);
+        set_before_exit(move || *count.borrow_mut() += 1);
+        before_exit();
+        before_exit();
+        assert_eq!(*runs.borrow(), 1);
+    }
+
+    #[test]
+    fn configured_exit_code() {
+        const CHILD: &str = "WE_CLAP_EXIT_CODE_CHILD";
+
+        if std::env::var_os(CHILD).is_some() {
+            crate::set_config(crate::Config {
+                exit_codes: [(ErrorKind::MissingRequiredArgument, 42)].into(),
+                ..crate::Config::default()
+            });
+            we_set_pre_exit_hook(|_, kind| eprintln!("hook saw {kind:?}"));
+            set_before_exit(|| eprintln!("cleaned up"));
+            let err = Command::new("app")
+                .arg(Arg::new("name").long("name").required(true))
+                .try_get_matches_from(["app"])
+                .unwrap_err();
+            native_exit(&err);
+        }
+
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "exit::tests::configured_exit_code",
+                "--nocapture",
+            ])
+            .env(CHILD, "1")
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(42));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--name <name>"));
+        assert!(stderr.contains("hook saw MissingRequiredArgument\ncleaned up"));
+    }
+}
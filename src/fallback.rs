@@ -0,0 +1,90 @@
+//! # Parse with a fallback type
+//!
+//! Old links keep working when the args change, parse as the current
+//! struct and fall back to the old one.
+
+use clap::error::{Error, ErrorKind};
+use clap::Parser;
+use std::ffi::OsString;
+
+/// # One of two parsed types
+///
+/// Returned by [`WeParser::we_parse_fallback()`](crate::WeParser::we_parse_fallback()),
+/// `Left` for the first type tried and `Right` for the fallback.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Either<T, U> {
+    /// Parsed as the first type.
+    Left(T),
+    /// Parsed as the fallback type.
+    Right(U),
+}
+
+/// Parse `T`, or `U` when `T` fails.
+///
+/// Help and version requests, and any error from `U`, are returned as is.
+pub(crate) fn parse_fallback<T, U>(
+    args: Vec<OsString>,
+    fallback_args: impl FnOnce() -> Result<Vec<OsString>, Error>,
+) -> Result<Either<T, U>, Error>
+where
+    T: Parser,
+    U: Parser,
+{
+    match T::try_parse_from(args) {
+        Ok(opts) => Ok(Either::Left(opts)),
+        Err(err)
+            if matches!(
+                err.kind(),
+                ErrorKind::DisplayHelp | ErrorKind::DisplayVersion
+            ) =>
+        {
+            Err(err)
+        }
+        Err(_) => U::try_parse_from(fallback_args()?).map(Either::Right),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translate::tests::url_args;
+
+    #[derive(Parser, Debug, PartialEq)]
+    struct OptsV2 {
+        #[arg(long)]
+        width: u32,
+        #[arg(long)]
+        height: u32,
+    }
+
+    #[derive(Parser, Debug, PartialEq)]
+    struct OptsV1 {
+        #[arg(long)]
+        size: u32,
+    }
+
+    fn parse(url: &str) -> Result<Either<OptsV2, OptsV1>, Error> {
+        let args = || url_args(url).into_iter().map(OsString::from).collect();
+        parse_fallback(args(), || Ok(args()))
+    }
+
+    #[test]
+    fn fallback_order() {
+        assert_eq!(
+            parse("/?width=3&height=4").unwrap(),
+            Either::Left(OptsV2 {
+                width: 3,
+                height: 4
+            })
+        );
+        assert_eq!(
+            parse("/?size=5").unwrap(),
+            Either::Right(OptsV1 { size: 5 })
+        );
+
+        let err = parse("/?depth=6").unwrap_err();
+        assert!(err.to_string().contains("--size"), "{err}");
+        let err = parse("/?--help").unwrap_err();
+        assert!(err.to_string().contains("--width"), "{err}");
+    }
+}
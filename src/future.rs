@@ -0,0 +1,199 @@
+//! # Async helpers
+//!
+//! Small futures for the async parse functions, so we_clap doesn't need an
+//! async runtime.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Resolves to the source output, or `None` if abort resolves first.
+pub(crate) struct Abortable<S, A> {
+    source: Pin<Box<S>>,
+    abort: Pin<Box<A>>,
+}
+
+impl<S, A> Abortable<S, A>
+where
+    S: Future,
+    A: Future<Output = ()>,
+{
+    pub(crate) fn new(source: S, abort: A) -> Self {
+        Self {
+            source: Box::pin(source),
+            abort: Box::pin(abort),
+        }
+    }
+}
+
+impl<S, A> Future for Abortable<S, A>
+where
+    S: Future,
+    A: Future<Output = ()>,
+{
+    type Output = Option<S::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.abort.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+        self.source.as_mut().poll(cx).map(Some)
+    }
+}
+
+/// # Future for a web [`AbortSignal`]
+///
+/// Resolves when the signal is aborted.  Create with [`abort_signal`].
+///
+/// (For use on web/wasm : with "web-abort" feature)
+///
+/// [`AbortSignal`]: https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal
+#[cfg(all(target_arch = "wasm32", feature = "web-abort"))]
+pub struct AbortSignalFuture {
+    signal: web_sys::AbortSignal,
+    waker: std::rc::Rc<std::cell::RefCell<Option<std::task::Waker>>>,
+    listener: wasm_bindgen::closure::Closure<dyn FnMut()>,
+}
+
+/// # Turn a web [`AbortSignal`] into a future
+///
+/// The future resolves when the signal is aborted, ready to pass as the
+/// `abort` of
+/// [`WeParser::we_parse_async_abortable()`](crate::WeParser::we_parse_async_abortable()).
+///
+/// (For use on web/wasm : with "web-abort" feature)
+///
+/// [`AbortSignal`]: https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal
+#[cfg(all(target_arch = "wasm32", feature = "web-abort"))]
+#[must_use]
+pub fn abort_signal(signal: &web_sys::AbortSignal) -> AbortSignalFuture {
+    use wasm_bindgen::JsCast;
+
+    let waker: std::rc::Rc<std::cell::RefCell<Option<std::task::Waker>>> = Default::default();
+    let wake = std::rc::Rc::clone(&waker);
+    let listener = wasm_bindgen::closure::Closure::<dyn FnMut()>::new(move || {
+        if let Some(waker) = wake.borrow_mut().take() {
+            waker.wake();
+        }
+    });
+    let _ = signal.add_event_listener_with_callback("abort", listener.as_ref().unchecked_ref());
+    AbortSignalFuture {
+        signal: signal.clone(),
+        waker,
+        listener,
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-abort"))]
+impl Future for AbortSignalFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.signal.aborted() {
+            Poll::Ready(())
+        } else {
+            *self.waker.borrow_mut() = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-abort"))]
+impl Drop for AbortSignalFuture {
+    fn drop(&mut self) {
+        use wasm_bindgen::JsCast;
+
+        let _ = self
+            .signal
+            .remove_event_listener_with_callback("abort", self.listener.as_ref().unchecked_ref());
+    }
+}
+
+/// The outcome of a promise once settled, and the waker of its future.
+#[cfg(all(target_arch = "wasm32", feature = "web-config-fetch"))]
+type Settled = std::rc::Rc<
+    std::cell::RefCell<(
+        Option<Result<wasm_bindgen::JsValue, wasm_bindgen::JsValue>>,
+        Option<std::task::Waker>,
+    )>,
+>;
+
+/// Resolves to the outcome of a JavaScript promise.
+#[cfg(all(target_arch = "wasm32", feature = "web-config-fetch"))]
+pub(crate) struct PromiseFuture {
+    settled: Settled,
+    _resolve: wasm_bindgen::closure::Closure<dyn FnMut(wasm_bindgen::JsValue)>,
+    _reject: wasm_bindgen::closure::Closure<dyn FnMut(wasm_bindgen::JsValue)>,
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-config-fetch"))]
+impl PromiseFuture {
+    pub(crate) fn new(promise: &js_sys::Promise) -> Self {
+        use wasm_bindgen::{closure::Closure, JsValue};
+
+        let settled: Settled = std::rc::Rc::default();
+        let settle = |ok: bool| {
+            let settled = std::rc::Rc::clone(&settled);
+            Closure::<dyn FnMut(JsValue)>::new(move |value: JsValue| {
+                let (result, waker) = &mut *settled.borrow_mut();
+                *result = Some(if ok { Ok(value) } else { Err(value) });
+                if let Some(waker) = waker.take() {
+                    waker.wake();
+                }
+            })
+        };
+        let (resolve, reject) = (settle(true), settle(false));
+        let _ = promise.then2(&resolve, &reject);
+        Self {
+            settled,
+            _resolve: resolve,
+            _reject: reject,
+        }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-config-fetch"))]
+impl Future for PromiseFuture {
+    type Output = Result<wasm_bindgen::JsValue, wasm_bindgen::JsValue>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (result, waker) = &mut *self.settled.borrow_mut();
+        match result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                *waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Run a future that needs no waking to completion.
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let mut cx = Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::{pending, ready};
+
+    #[test]
+    fn abort_first() {
+        let aborted = block_on(Abortable::new(pending::<u8>(), ready(())));
+        assert_eq!(aborted, None);
+    }
+
+    #[test]
+    fn source_first() {
+        let done = block_on(Abortable::new(ready(7), pending()));
+        assert_eq!(done, Some(7));
+    }
+}
@@ -0,0 +1,478 @@
+//! # Pre-rendered help
+//!
+//! Rendering help walks the whole [`clap::Command`].  Apps that show help
+//! often, or on first paint, can render it once and keep it in a
+//! [`HelpCache`].
+
+use clap::error::{self, ErrorKind};
+use clap::{ArgAction, Command, Parser};
+use std::cell::Cell;
+use std::ffi::OsString;
+
+thread_local! {
+    static WIDTH: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// # Help, long help and usage rendered once
+///
+/// Create with [`WeCommand::we_cache_help()`](crate::WeCommand::we_cache_help()).
+/// The strings are plain text, ready for the console, a popup alert or
+/// your own UI.  Printing from the cache doesn't render or allocate.
+///
+/// The cache is not tied to the command.  If the command changes, by
+/// adding args or changing the about text, create a new cache.
+///
+/// # Example
+/// ``` rust
+/// use clap::Command;
+/// use we_clap::WeCommand;
+///
+/// let mut cli = Command::new("Native and Web Program");
+/// let cache = cli.we_cache_help();
+///
+/// cache.print_help(); // As often as needed.
+/// assert!(cache.usage().contains("Usage"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HelpCache {
+    help: String,
+    long_help: String,
+    usage: String,
+}
+
+impl HelpCache {
+    /// Render the help, long help and usage of a command.
+    pub(crate) fn new(command: &mut Command) -> Self {
+        Self {
+            help: command.render_help().to_string(),
+            long_help: command.render_long_help().to_string(),
+            usage: command.render_usage().to_string(),
+        }
+    }
+
+    /// The help message, like `-h`.
+    #[must_use]
+    pub fn help(&self) -> &str {
+        &self.help
+    }
+
+    /// The long help message, like `--help`.
+    #[must_use]
+    pub fn long_help(&self) -> &str {
+        &self.long_help
+    }
+
+    /// The usage line.
+    #[must_use]
+    pub fn usage(&self) -> &str {
+        &self.usage
+    }
+
+    /// Print the help message on native or the web.\
+    /// Native output is to stdout.\
+    /// Web output is to console or popup alert.
+    pub fn print_help(&self) {
+        crate::output::print(&self.help);
+    }
+
+    /// Print the long help message on native or the web.\
+    /// Native output is to stdout.\
+    /// Web output is to console or popup alert.
+    pub fn print_long_help(&self) {
+        crate::output::print(&self.long_help);
+    }
+}
+
+/// # A help or version request
+///
+/// Returned by
+/// [`WeCommand::we_wants_help_or_version()`](crate::WeCommand::we_wants_help_or_version()).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HelpOrVersion {
+    /// A help flag like `--help`, or the `help` subcommand.
+    Help,
+    /// A version flag like `--version`.
+    Version,
+}
+
+/// Scan args for the first help or version request, without parsing.
+///
+/// Knows clap's default `-h`, `--help`, `-V` and `--version` flags, unless
+/// disabled, and any args with a help or version action.  Stops at `--`.
+pub(crate) fn wants_help_or_version(command: &Command, args: &[OsString]) -> Option<HelpOrVersion> {
+    let mut help = Vec::new();
+    let mut version = Vec::new();
+    if !command.is_disable_help_flag_set() {
+        help.extend(["-h".to_string(), "--help".to_string()]);
+    }
+    if !command.is_disable_version_flag_set()
+        && (command.get_version().is_some() || command.get_long_version().is_some())
+    {
+        version.extend(["-V".to_string(), "--version".to_string()]);
+    }
+    for arg in command.get_arguments() {
+        let flags = match arg.get_action() {
+            ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong => &mut help,
+            ArgAction::Version => &mut version,
+            _ => continue,
+        };
+        flags.extend(
+            arg.get_short_and_visible_aliases()
+                .into_iter()
+                .flatten()
+                .map(|short| format!("-{short}")),
+        );
+        flags.extend(
+            arg.get_long_and_visible_aliases()
+                .into_iter()
+                .flatten()
+                .map(|long| format!("--{long}")),
+        );
+    }
+    let help_subcommand = command.has_subcommands() && !command.is_disable_help_subcommand_set();
+
+    for arg in args.iter().skip(1) {
+        let arg = arg.to_string_lossy();
+        if arg == "--" {
+            break;
+        }
+        if help.iter().any(|flag| *flag == arg) || (help_subcommand && arg == "help") {
+            return Some(HelpOrVersion::Help);
+        }
+        if version.iter().any(|flag| *flag == arg) {
+            return Some(HelpOrVersion::Version);
+        }
+    }
+    None
+}
+
+/// # Set the help width on the web
+///
+/// The width, in columns, that help is laid out for on the web, given to
+/// [`Command::term_width`](clap::Command::term_width) before rendering
+/// help or parsing.  A narrow popup alert wants 60, devtools may take 120.
+/// `0` means no limit.
+///
+/// Clap only lays help out for a width with its `wrap_help` feature, turn
+/// it on in your `Cargo.toml`.  It then wraps text and puts the help of
+/// long args on the next line.  Without it help lines are as long as they
+/// are written.
+///
+/// Unset, clap uses 100 columns on the web, where there is no terminal to
+/// measure.  Not used on native, where clap measures the terminal.
+///
+/// # Example
+/// ``` rust
+/// we_clap::set_help_width(60);
+/// ```
+pub fn set_help_width(width: usize) {
+    WIDTH.set(Some(width));
+}
+
+/// The command with the [help width](set_help_width) on the web, if one
+/// is set.
+pub(crate) fn sized(command: Command) -> Command {
+    match WIDTH.get() {
+        Some(width) if cfg!(target_arch = "wasm32") => command.term_width(width),
+        _ => command,
+    }
+}
+
+/// Give the command the [help width](set_help_width) on the web, if one is
+/// set.
+pub(crate) fn size(command: &mut Command) {
+    *command = sized(std::mem::take(command));
+}
+
+/// Turn clap's `next_line_help` on or off for the command.
+pub(crate) fn set_next_line(command: &mut Command, enabled: bool) {
+    *command = std::mem::take(command).next_line_help(enabled);
+}
+
+/// The usage as a compact placeholder, without the `Usage:` label and one
+/// line per usage.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-placeholder")))]
+pub(crate) fn placeholder(command: &mut Command) -> String {
+    let usage = command.render_usage().to_string();
+    usage
+        .lines()
+        .map(|line| line.trim().trim_start_matches("Usage:").trim_start())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Put the text in the placeholder of a textarea or input element, or as
+/// the text of any other element.
+#[cfg(all(target_arch = "wasm32", feature = "web-placeholder"))]
+pub(crate) fn into_placeholder(id: &str, text: &str) -> bool {
+    let Some(element) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(id))
+    else {
+        return false;
+    };
+    if matches!(
+        element.tag_name().to_lowercase().as_str(),
+        "textarea" | "input"
+    ) {
+        let _ = element.set_attribute("placeholder", text);
+    } else {
+        element.set_text_content(Some(text));
+    }
+    true
+}
+
+/// Parse the args, or show the help with `show` and give `None`.
+///
+/// Help and version requests show their message.  Errors show the error
+/// message followed by the help.
+pub(crate) fn parse_or_help<T: Parser>(
+    args: error::Result<Vec<OsString>>,
+    show: impl FnOnce(&str),
+) -> Option<T> {
+    let err = match args.and_then(T::try_parse_from) {
+        Ok(opts) => return Some(opts),
+        Err(err) => err,
+    };
+    match err.kind() {
+        ErrorKind::DisplayHelp
+        | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand
+        | ErrorKind::DisplayVersion => show(&err.to_string()),
+        _ => show(&format!("{err}\n{}", sized(T::command()).render_help())),
+    }
+    None
+}
+
+/// Show text in the element with the id, or in the web output after a
+/// console warning when there is no such element.
+pub(crate) fn show_in(id: &str, text: &str) {
+    #[cfg(all(target_arch = "wasm32", feature = "web-help-element"))]
+    if let Some(element) = crate::output::element(id) {
+        element.set_text_content(Some(text));
+        return;
+    }
+    #[cfg(not(all(target_arch = "wasm32", feature = "web-help-element")))]
+    let _ = id;
+    crate::output::print(text);
+}
+
+/// Clap indents each arg with two spaces.
+const CLAP_INDENT: usize = 2;
+
+/// Change the indentation of rendered help.
+///
+/// Every indented line is shifted by the same amount, so the arg column
+/// starts at `indent` and the help column keeps its alignment.
+pub(crate) fn reindent(help: &str, indent: usize) -> String {
+    let mut reindented = String::with_capacity(help.len());
+    for line in help.lines() {
+        let text = line.trim_start_matches(' ');
+        let lead = line.len() - text.len();
+        if lead > 0 && !text.is_empty() {
+            let lead = lead.saturating_sub(CLAP_INDENT) + indent;
+            reindented.extend(std::iter::repeat_n(' ', lead));
+        }
+        reindented.push_str(text);
+        reindented.push('\n');
+    }
+    reindented
+}
+
+/// The names of the visible subcommands, each shown in a console group.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-console-groups")))]
+pub(crate) fn group_names(command: &Command) -> Vec<String> {
+    command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set())
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect()
+}
+
+/// Print the help of each subcommand in a console group after the help of
+/// the command, nested like the subcommands.  Only when the output is the
+/// console.
+#[cfg(all(target_arch = "wasm32", feature = "web-console-groups"))]
+pub(crate) fn print_groups(command: &mut Command, long: bool) {
+    if !crate::output::to_console() {
+        return;
+    }
+    command.build();
+    for name in group_names(command) {
+        let Some(subcommand) = command.find_subcommand_mut(&name) else {
+            continue;
+        };
+        web_sys::console::group_1(&name.as_str().into());
+        let help = if long {
+            subcommand.render_long_help()
+        } else {
+            subcommand.render_help()
+        };
+        crate::output::print(&crate::output::styled(&help));
+        print_groups(subcommand, long);
+        web_sys::console::group_end();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+
+    #[test]
+    fn console_groups() {
+        let mut command = Command::new("app")
+            .subcommand(Command::new("run").arg(Arg::new("fast").long("fast")))
+            .subcommand(Command::new("secret").hide(true));
+        command.build();
+        assert_eq!(group_names(&command), ["run", "help"]);
+        let run = command.find_subcommand_mut("run").unwrap();
+        assert!(run.render_help().to_string().contains("Usage: app run"));
+    }
+
+    #[test]
+    fn help_width() {
+        let command = || Command::new("demo").arg(Arg::new("name").long("name"));
+        set_help_width(60);
+        assert_eq!(WIDTH.get(), Some(60));
+
+        // Native help is laid out for the terminal.
+        assert_eq!(
+            sized(command()).render_help().to_string(),
+            command().render_help().to_string()
+        );
+    }
+
+    #[test]
+    fn matches_fresh_render() {
+        let mut command = Command::new("cached")
+            .about("Short about")
+            .long_about("A much longer about")
+            .arg(Arg::new("name").long("name").help("Your name"));
+
+        let cache = HelpCache::new(&mut command);
+        assert_eq!(cache.help(), command.render_help().to_string());
+        assert_eq!(cache.long_help(), command.render_long_help().to_string());
+        assert_eq!(cache.usage(), command.render_usage().to_string());
+        assert!(cache.long_help().contains("A much longer about"));
+    }
+
+    #[test]
+    fn help_or_version_request() {
+        use clap::Arg;
+        use cliw::url_args::UrlArgs;
+
+        let command = Command::new("peek")
+            .version("1.0")
+            .subcommand(Command::new("run"))
+            .arg(Arg::new("info").long("info").action(ArgAction::Help));
+        let wants = |url: &str| {
+            let args: Vec<OsString> = UrlArgs::from(url).collect();
+            wants_help_or_version(&command, &args)
+        };
+
+        assert_eq!(wants("/?--help"), Some(HelpOrVersion::Help));
+        assert_eq!(wants("/?run&-h"), Some(HelpOrVersion::Help));
+        assert_eq!(wants("/?help"), Some(HelpOrVersion::Help));
+        assert_eq!(wants("/?--info"), Some(HelpOrVersion::Help));
+        assert_eq!(wants("/?--version"), Some(HelpOrVersion::Version));
+        assert_eq!(wants("/?-V"), Some(HelpOrVersion::Version));
+        assert_eq!(wants("/?run"), None);
+        assert_eq!(wants("/?--&--help"), None);
+    }
+
+    #[test]
+    fn compact_indent() {
+        let mut command = Command::new("compact")
+            .arg(Arg::new("name").long("name").help("Your name"))
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .help("Talk more"),
+            );
+        let help = command.render_help().to_string();
+        let column = |help: &str| {
+            let line = help
+                .lines()
+                .find(|line| line.contains("Your name"))
+                .unwrap();
+            line.find("Your name").unwrap()
+        };
+
+        let compact = reindent(&help, 0);
+        assert_eq!(column(&compact) + 2, column(&help));
+        assert!(compact
+            .lines()
+            .any(|line| line.starts_with("-v, --verbose")));
+        assert_eq!(reindent(&help, 2).trim_end(), help.trim_end());
+    }
+
+    #[test]
+    fn next_line_layout() {
+        let mut command =
+            Command::new("narrow").arg(Arg::new("name").long("name").help("Your name"));
+
+        set_next_line(&mut command, true);
+        let help = command.render_help().to_string();
+        assert!(
+            help.contains("--name <name>\n          Your name"),
+            "{help}"
+        );
+
+        set_next_line(&mut command, false);
+        let help = command.render_help().to_string();
+        assert!(help.contains("--name <name>  Your name"), "{help}");
+    }
+
+    #[test]
+    fn usage_placeholder() {
+        let mut command = Command::new("builder")
+            .arg(Arg::new("name").long("name").required(true))
+            .subcommand(Command::new("run"))
+            .subcommand_negates_reqs(true);
+
+        assert_eq!(
+            placeholder(&mut command),
+            "builder --name <name>\nbuilder <COMMAND>"
+        );
+    }
+
+    #[test]
+    fn parse_or_help_cases() {
+        use crate::translate::tests::url_args;
+
+        #[derive(Parser, Debug)]
+        struct Opts {
+            /// Your name
+            #[arg(long)]
+            name: String,
+        }
+        let parse = |url: &str| {
+            let mut shown = None;
+            let args = url_args(url).into_iter().map(OsString::from).collect();
+            let opts = parse_or_help::<Opts>(Ok(args), |text| shown = Some(text.to_string()));
+            (opts, shown)
+        };
+
+        let (opts, shown) = parse("/?name=bob");
+        assert_eq!(opts.unwrap().name, "bob");
+        assert_eq!(shown, None);
+
+        let (opts, shown) = parse("/?--help");
+        assert!(opts.is_none());
+        let shown = shown.unwrap();
+        assert!(
+            shown.contains("Your name") && !shown.contains("error"),
+            "{shown}"
+        );
+
+        let (opts, shown) = parse("/");
+        assert!(opts.is_none());
+        let shown = shown.unwrap();
+        assert!(
+            shown.contains("error:") && shown.contains("Your name"),
+            "{shown}"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! # Help as HTML
+//!
+//! The help of a command as a semantic HTML fragment, for a docs site or
+//! page styled with its own CSS, instead of monospaced text.
+
+use clap::{Arg, Command};
+
+/// The help of the command as an HTML fragment.
+pub(crate) fn render(command: &Command) -> String {
+    let mut command = command.clone();
+    command.build();
+    let mut html = String::from("<div class=\"we-clap-help\">\n");
+    if let Some(about) = command.get_long_about().or(command.get_about()) {
+        html.push_str(&format!(
+            "<p class=\"about\">{}</p>\n",
+            escape(&about.to_string())
+        ));
+    }
+    let usage = command.render_usage().to_string();
+    let usage = usage.trim().trim_start_matches("Usage:").trim();
+    html.push_str(&format!(
+        "<p class=\"usage\">Usage: <code>{}</code></p>\n",
+        escape(usage)
+    ));
+    for (heading, args) in sections(&command) {
+        html.push_str(&format!("<h3>{}</h3>\n", escape(heading)));
+        html.push_str("<table class=\"args\">\n");
+        for arg in args {
+            html.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td></tr>\n",
+                escape(&label(arg)),
+                details(arg)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    let subcommands: Vec<&Command> = command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set())
+        .collect();
+    if !subcommands.is_empty() {
+        let heading = command.get_subcommand_help_heading().unwrap_or("Commands");
+        html.push_str(&format!("<h3>{}</h3>\n", escape(heading)));
+        html.push_str("<table class=\"commands\">\n");
+        for subcommand in subcommands {
+            let about = subcommand.get_about().map(ToString::to_string);
+            html.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td></tr>\n",
+                escape(subcommand.get_name()),
+                escape(&about.unwrap_or_default())
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</div>\n");
+    html
+}
+
+/// The visible args under their headings, positionals under "Arguments"
+/// and options under "Options" unless given a `help_heading`, in the order
+/// clap shows them.
+fn sections(command: &Command) -> Vec<(&str, Vec<&Arg>)> {
+    let mut sections: Vec<(&str, Vec<&Arg>)> =
+        vec![("Arguments", Vec::new()), ("Options", Vec::new())];
+    for arg in command.get_arguments().filter(|arg| !arg.is_hide_set()) {
+        let heading = arg.get_help_heading().unwrap_or(if arg.is_positional() {
+            "Arguments"
+        } else {
+            "Options"
+        });
+        match sections.iter_mut().find(|(name, _)| *name == heading) {
+            Some((_, args)) => args.push(arg),
+            None => sections.push((heading, vec![arg])),
+        }
+    }
+    sections.retain(|(_, args)| !args.is_empty());
+    sections
+}
+
+/// The flags and value names of an arg, `-n, --name <NAME>`.
+fn label(arg: &Arg) -> String {
+    let value_names: Vec<String> = match arg.get_value_names() {
+        Some(names) => names.iter().map(ToString::to_string).collect(),
+        None => vec![arg.get_id().to_string()],
+    };
+    if arg.is_positional() {
+        let names = value_names.join(" ");
+        return if arg.is_required_set() {
+            format!("<{names}>")
+        } else {
+            format!("[{names}]")
+        };
+    }
+    let mut flags = Vec::new();
+    if let Some(short) = arg.get_short() {
+        flags.push(format!("-{short}"));
+    }
+    if let Some(long) = arg.get_long() {
+        flags.push(format!("--{long}"));
+    }
+    let mut label = flags.join(", ");
+    if arg.get_action().takes_values() {
+        for name in value_names {
+            label.push_str(&format!(" <{name}>"));
+        }
+    }
+    label
+}
+
+/// The help text of an arg, with its default and possible values, escaped.
+fn details(arg: &Arg) -> String {
+    let mut details = escape(
+        &arg.get_long_help()
+            .or(arg.get_help())
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+    );
+    let defaults: Vec<String> = arg
+        .get_default_values()
+        .iter()
+        .map(|value| value.to_string_lossy().into_owned())
+        .collect();
+    if !defaults.is_empty() && !arg.is_hide_default_value_set() {
+        details.push_str(&format!(
+            " <span class=\"default\">[default: {}]</span>",
+            escape(&defaults.join(", "))
+        ));
+    }
+    let possible: Vec<String> = arg
+        .get_possible_values()
+        .iter()
+        .filter(|value| !value.is_hide_set())
+        .map(|value| value.get_name().to_string())
+        .collect();
+    if !possible.is_empty() && !arg.is_hide_possible_values_set() {
+        details.push_str(&format!(
+            " <span class=\"possible\">[possible values: {}]</span>",
+            escape(&possible.join(", "))
+        ));
+    }
+    details.trim_start().to_string()
+}
+
+/// Escape text for HTML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn help_table() {
+        let command = Command::new("app")
+            .about("Does <things>")
+            .arg(Arg::new("file").required(true).help("The input"))
+            .arg(
+                Arg::new("level")
+                    .short('l')
+                    .long("level")
+                    .value_name("N")
+                    .default_value("3")
+                    .help("How much"),
+            )
+            .arg(
+                Arg::new("color")
+                    .long("color")
+                    .value_parser(["auto", "never"])
+                    .help_heading("Display"),
+            )
+            .arg(Arg::new("secret").long("secret").hide(true))
+            .subcommand(Command::new("run").about("Run it"));
+        let html = render(&command);
+        assert!(html.contains("<p class=\"about\">Does &lt;things&gt;</p>"));
+        assert!(html.contains("Usage: <code>app [OPTIONS] &lt;file&gt; [COMMAND]</code>"));
+        assert!(html.contains("<h3>Arguments</h3>"));
+        assert!(html.contains("<tr><td><code>&lt;file&gt;</code></td><td>The input</td></tr>"));
+        assert!(html.contains(
+            "<tr><td><code>-l, --level &lt;N&gt;</code></td>\
+             <td>How much <span class=\"default\">[default: 3]</span></td></tr>"
+        ));
+        let display = html.find("<h3>Display</h3>").unwrap();
+        assert!(html.find("<h3>Options</h3>").unwrap() < display);
+        assert!(html[display..].contains("[possible values: auto, never]"));
+        assert!(html.contains("--help"));
+        assert!(!html.contains("secret"));
+        assert!(html.contains("<tr><td><code>run</code></td><td>Run it</td></tr>"));
+    }
+}
@@ -27,6 +27,158 @@
 //! * web-console
 //!     - Enable output to browser console.
 //!     - Set by default
+//! * derive
+//!     - `#[derive(WeParser)]` instead of writing the empty
+//!       `impl we_clap::WeParser for Opts {}`.
+//! * web-referrer
+//!     - Enable `WeParser::we_parse_from_referrer()`.
+//! * web-abort
+//!     - Enable `abort_signal` to abort async parsing with an `AbortSignal`.
+//! * web-env
+//!     - Enable [`we_env()`] lookups on the web.
+//!     - Args with clap's `env("MY_VAR")` read `MY_VAR` with [`we_env()`]
+//!       on the web when the url doesn't give them.  Turns on clap's env
+//!       feature.
+//! * web-dom-events
+//!     - Dispatch an [`ERROR_EVENT`] DOM event for parse errors on the web.
+//! * web-placeholder
+//!     - Enable [`WeCommand::we_help_into_placeholder()`] on the web.
+//! * web-help-element
+//!     - Enable [`WeParser::we_parse_or_help()`] and
+//!       [`WeCommand::we_print_help_to_element()`] to show help in an
+//!       element.
+//! * web-panic-hook
+//!     - Send panic messages to the web error output, installed by the
+//!       first web parse, see [`install_panic_alert()`].
+//! * web-perf-marks
+//!     - Mark the `weclap:read`, `weclap:translate` and `weclap:parse`
+//!       phases of a web parse on the performance timeline.
+//! * web-output-target
+//!     - Choose console, popup alert or an element for web output at
+//!       runtime with [`set_output_target()`].
+//! * web-console-styled
+//!     - Color help and errors in the browser console with `%c` CSS
+//!       styling, see [`ansi_to_console_css()`].
+//! * web-both
+//!     - Output to both a popup alert, as plain text, and the browser
+//!       console, colored.  Turns on "web-alert" and "web-console-styled".
+//! * web-console-groups
+//!     - After the help of [`WeCommand::we_print_help()`] and
+//!       [`WeCommand::we_print_long_help()`], show the help of each
+//!       subcommand in a collapsible console group.
+//! * web-hash
+//!     - Read args from the url `#` fragment with [`set_arg_source()`].
+//! * web-storage
+//!     - Read args saved in `localStorage` with [`set_arg_source()`], save
+//!       them with [`save_args_to_local_storage()`].
+//! * web-single-param
+//!     - Read a whole command line from one query parameter, `?args=...`,
+//!       with [`set_arg_source()`].
+//! * web-path
+//!     - Read positional args from the url path with [`set_arg_source()`],
+//!       see [`ArgSource::Path`].
+//! * web-subscribe
+//!     - Parse the args again when the url changes without a reload, with
+//!       [`subscribe_to_args()`].
+//! * wasm-node
+//!     - Read Node's `process.argv` when wasm runs under Node, see
+//!       [wasm without a browser](#wasm-without-a-browser).
+//! * web-worker
+//!     - Read args from the script url of a Web Worker, see
+//!       [wasm without a browser](#wasm-without-a-browser).
+//! * web-post-message
+//!     - Wait for args posted by a parent frame with
+//!       [`WeParser::we_parse_async()`], see [`ArgSource::PostMessage`].
+//! * web-config-fetch
+//!     - Fetch the config file url of
+//!       [`WeParser::we_parse_with_config()`] on the web.
+//! * web-debug
+//!     - Log the query string and the args given to clap to the browser
+//!       console on each web parse, see [`set_debug()`].
+//! * json-args
+//!     - Read args from a JSON object in one query parameter with
+//!       [`set_arg_source()`], see [`ArgSource::Json`].
+//! * test-util
+//!     - Enable `capture_output` to check what was printed in tests, like
+//!       `wasm-bindgen-test` cases.
+//!
+//! # Query strings on the web
+//!
+//! The url query strings are translated into command line arguments.
+//! A `key=value` query becomes `--key=value`, or `-k=value` for a one
+//! letter key.  A `key` or `key=` query for a flag that takes no value,
+//! like a `bool`, becomes `--key`, so `?verbose&output=foo` works.  Other
+//! query strings are passed to clap unchanged.
+//!
+//! Count flags, like `-vvv`, are a repeated key, `?v&v&v`, or a number for
+//! a key in [`Config::count_params`], `?v=3`.
+//! See [`Config`] to change how the url is translated.
+//!
+//! An arg can take several values, with `num_args = 2` for example.  Repeat
+//! the key for each value, `?point=1&point=2` gives `--point 1 2`.  Or use a
+//! `value_delimiter` on the arg, `?point=1,2`.  When the count is wrong
+//! clap reports the error as usual.
+//!
+//! Values starting with `-`, like `?expr=-x%2B1`, are safe in the default
+//! `--expr=-x+1` form.  Positional values like `?_=-x`, and every value
+//! with [`Config::use_equals_form`] off, need clap's `allow_hyphen_values`
+//! on the arg, as on native.  A `+` in the query string is a space, like
+//! in a form submission, write a plus as `%2B`.
+//!
+//! | url                      | arguments              |
+//! |--------------------------|------------------------|
+//! | `?offset=-5`             | `--offset=-5`          |
+//! | `?n=bob`                 | `-n=bob`               |
+//! | `?_=file.txt`            | `file.txt`             |
+//! | `?verbose&_=file.txt`    | `--verbose file.txt`   |
+//! | `?--verbose&file.txt`    | `--verbose file.txt`   |
+//!
+//! # no_std translation core
+//!
+//! The translation rules themselves, [`we_translate_queries()`] and
+//! [`QueryRules`], need only `core` and `alloc`.  They are the `no_std`
+//! part of the crate, for embeddings that read the url another way.  The
+//! rest of we_clap uses clap and needs `std`.
+//!
+//! # Wasm without a browser
+//!
+//! The same clap code can run in a Web Worker, or as a command line tool
+//! compiled to wasm.  When there is no `window`, no page to read a url
+//! from, the args are from:
+//!
+//! * In a Web Worker, the url of its script, `self.location`, read like a
+//!   page url.  Needs the "web-worker" feature, detected at runtime so one
+//!   binary works on the main thread and in workers.
+//! * Under WASI, `wasm32-wasip1` and friends, [`std::env::args_os`].
+//! * When those are empty, as under Node, Node's `process.argv` without the
+//!   node binary.  Needs the "wasm-node" feature.
+//! * Otherwise no args, only the program name.
+//!
+//! The [`ArgSource`] is not used without a page or worker.  Output still
+//! goes to the console, the worker's own console in a worker, which Node
+//! prints to stdout and stderr.  There are no popup alerts without a
+//! `window`.
+//!
+//! # Help and version on the web
+//!
+//! Help and version messages go to the regular web output, errors to the
+//! error output.  With the "web-console" feature that is:
+//!
+//! * `console.log` : [`ErrorKind::DisplayHelp`](clap::error::ErrorKind::DisplayHelp)
+//!   and [`ErrorKind::DisplayVersion`](clap::error::ErrorKind::DisplayVersion).
+//! * `console.error` : every other kind, genuine failures.  This includes
+//!   [`ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand`](clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand),
+//!   which clap prints to stderr on native.
+//!
+//! A version request also calls the [`we_on_version`]
+//! callback, if one is registered.  With clap's `propagate_version` set a
+//! `--version` on any subcommand is handled the same way.
+//!
+//! A `?color=never` in the url turns colors off, `?color=always` keeps
+//! them for the console, and `?color=auto`, the default, colors the console
+//! with the "web-console-styled" feature.  Popup alerts are always plain.
+//! The param is taken out of the args, unless the command has its own
+//! `--color` arg.
 //!
 //! # Example
 //! ## we_clap_demo
@@ -83,13 +235,412 @@
 //! [`console`]: https://developer.mozilla.org/en-US/docs/Web/API/console
 //! [`UrlArgs`]: https://docs.rs/cliw/latest/cliw/url_args/struct.UrlArgs.html
 
-use clap::{error, ArgMatches, Command, Parser};
+extern crate alloc;
+
+use clap::{error, Arg, ArgMatches, Command, Parser};
+use std::ffi::OsString;
+use std::future::Future;
 
+#[cfg(not(target_arch = "wasm32"))]
+use exit::native_exit;
 #[cfg(target_arch = "wasm32")]
-use clap::error::ErrorKind;
+use exit::web_exit;
+
+mod config;
+mod config_file;
+mod debug;
+mod env;
+mod exit;
+mod fallback;
+mod future;
+mod help;
+mod help_html;
+mod matches;
+mod menu;
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-post-message")))]
+mod message;
+mod outcome;
+mod output;
+mod panic_hook;
+mod perf;
+mod query;
+mod query_map;
+mod sanitize;
+mod shell;
+mod source;
+mod style;
+mod subscribe;
+mod translate;
+#[cfg(any(test, target_arch = "wasm32"))]
+mod unexpected;
+mod we_error;
+mod web_args;
+
+pub use config::{config, set_config, Config};
+pub use debug::set_debug;
+pub use env::{we_env, EnvSource};
+pub use exit::{set_before_exit, we_exit_code, we_set_pre_exit_hook};
+pub use fallback::Either;
+#[cfg(all(target_arch = "wasm32", feature = "web-abort"))]
+pub use future::{abort_signal, AbortSignalFuture};
+pub use help::{set_help_width, HelpCache, HelpOrVersion};
+pub use matches::{we_summary, we_to_query, we_to_url, WeMatches, WeToQuery};
+pub use menu::SubcommandInfo;
+pub use outcome::{we_classify, WeOutcome};
+#[cfg(feature = "test-util")]
+pub use output::capture_output;
+pub use output::{
+    active_sinks, set_output_callback, set_output_target, we_on_version, OutputKind, OutputTarget,
+    ERROR_EVENT,
+};
+pub use panic_hook::install_panic_alert;
+pub use query::{we_translate_queries, QueryRules};
+pub use query_map::{query_map, query_map_from};
+pub use sanitize::{we_check_chars, ControlChars};
+pub use shell::{shell_split, SplitError};
+pub use source::{
+    refresh_args, save_args_to_local_storage, set_arg_source, set_async_arg_source,
+    set_program_name, ArgSource, ProgramName, WeArgsSource,
+};
+pub use style::ansi_to_console_css;
+pub use subscribe::{subscribe_to_args, ArgsSubscription};
+pub use translate::{set_arg_aliases, set_arg_preprocessor, we_args};
+pub use we_error::{RenderedError, WeError};
+pub use web_args::WebArgs;
+
+/// # Derive [`WeParser`](trait@WeParser)
+///
+/// `#[derive(Parser, WeParser)]` writes the empty
+/// `impl we_clap::WeParser for Opts {}` for you.  Only the impl is
+/// generated, clap's derive is untouched.  With the "derive" feature.
+///
+/// # Example
+/// ``` rust
+/// use clap::Parser;
+/// use we_clap::WeParser;
+///
+/// #[derive(Parser, WeParser, Debug)]
+/// pub struct Opts {
+///     #[arg(short, long)]
+///     pub verbose: bool,
+/// }
+///
+/// let opts: Opts = Opts::we_parse_from::<Opts, _, _>(["app", "-v"]);
+/// assert!(opts.verbose);
+/// ```
+#[cfg(feature = "derive")]
+pub use we_clap_derive::WeParser;
+
+/// Required args named in a missing required argument error.
+fn missing_required(result: &error::Result<ArgMatches>) -> Vec<String> {
+    match result {
+        Err(err) => missing_in(err),
+        Ok(_) => Vec::new(),
+    }
+}
+
+/// The required args missing in an error, as clap shows them.
+fn missing_in(err: &error::Error) -> Vec<String> {
+    if err.kind() != error::ErrorKind::MissingRequiredArgument {
+        return Vec::new();
+    }
+    match err.get(error::ContextKind::InvalidArg) {
+        Some(error::ContextValue::Strings(missing)) => missing.clone(),
+        Some(error::ContextValue::String(missing)) => vec![missing.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// Parse the args, asking `prompt` for the missing required args until
+/// none are missing.  The answers are added to `args`.
+///
+/// Gives the error when a prompt is cancelled, or when the answers didn't
+/// fill in the missing args.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn parse_interactive<T: Parser>(
+    args: &mut Vec<String>,
+    mut prompt: impl FnMut(&str) -> Option<String>,
+) -> error::Result<T> {
+    let mut asked = Vec::new();
+    loop {
+        let err = match T::try_parse_from(args.iter()) {
+            Ok(opts) => return Ok(opts),
+            Err(err) => err,
+        };
+        let missing = missing_in(&err);
+        if missing.is_empty() || missing == asked {
+            return Err(err);
+        }
+        let Some(answers) = prompted_args(&missing, &mut prompt) else {
+            return Err(err);
+        };
+        args.extend(answers);
+        asked = missing;
+    }
+}
+
+/// Args for the answers to a `value for --name` prompt per missing arg,
+/// or `None` if one was cancelled.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn prompted_args(
+    missing: &[String],
+    prompt: &mut impl FnMut(&str) -> Option<String>,
+) -> Option<Vec<String>> {
+    missing
+        .iter()
+        .map(|missing| {
+            let name = missing.split_whitespace().next().unwrap_or(missing);
+            let value = prompt(&format!("value for {name}"))?;
+            Some(if name.starts_with('-') {
+                format!("{name}={value}")
+            } else {
+                value
+            })
+        })
+        .collect()
+}
+
+/// Validate the args, then parse them.
+async fn parse_validated<T, V, F>(args: Vec<OsString>, validate: V) -> Result<T, WeError>
+where
+    T: Parser,
+    V: Fn(&[String]) -> F,
+    F: Future<Output = Result<(), WeError>>,
+{
+    let strings: Vec<String> = args
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    validate(&strings).await?;
+    Ok(T::try_parse_from(args)?)
+}
+
+/// Parse the args, dropping each value that fails validation and parsing
+/// again, until they parse.  Gives the default and every error when an
+/// error has no value to drop.
+fn parse_lenient<T: Default>(
+    command: &Command,
+    mut args: Vec<String>,
+    parse: impl Fn(&[String]) -> error::Result<T>,
+) -> (T, Vec<error::Error>) {
+    let mut errors = Vec::new();
+    loop {
+        let err = match parse(&args) {
+            Ok(opts) => return (opts, errors),
+            Err(err) => err,
+        };
+        let dropped = matches!(
+            err.kind(),
+            error::ErrorKind::InvalidValue | error::ErrorKind::ValueValidation
+        ) && drop_value(command, &mut args, &err);
+        errors.push(err);
+        if !dropped {
+            return (T::default(), errors);
+        }
+    }
+}
+
+/// Remove the occurrence of the arg with the invalid value in an error,
+/// `--count=abc`, `--count abc` together or a positional `abc`.  Only the
+/// arg named by the error is dropped, not another with the same value.
+/// `false` if not found.
+fn drop_value(command: &Command, args: &mut Vec<String>, err: &error::Error) -> bool {
+    let (Some(error::ContextValue::String(invalid)), Some(error::ContextValue::String(value))) = (
+        err.get(error::ContextKind::InvalidArg),
+        err.get(error::ContextKind::InvalidValue),
+    ) else {
+        return false;
+    };
+    let name = invalid.split_whitespace().next().unwrap_or_default();
+    let found = if name.starts_with('-') {
+        find_arg(command, &|arg| {
+            flag_names(arg).iter().any(|flag| flag == name)
+        })
+        .and_then(|arg| option_value(command, args, arg, value))
+    } else {
+        positional_value(command, args, value)
+    };
+    match found {
+        Some(range) => {
+            args.drain(range);
+            true
+        }
+        None => false,
+    }
+}
+
+/// The arg of the command or its subcommands that matches.
+fn find_arg<'a>(command: &'a Command, matches: &dyn Fn(&Arg) -> bool) -> Option<&'a Arg> {
+    command
+        .get_arguments()
+        .find(|arg| matches(arg))
+        .or_else(|| {
+            command
+                .get_subcommands()
+                .find_map(|subcommand| find_arg(subcommand, matches))
+        })
+}
+
+/// The flags of an arg, `--count` and `-c` with their aliases.
+fn flag_names(arg: &Arg) -> Vec<String> {
+    let longs = arg.get_long_and_visible_aliases().into_iter().flatten();
+    let shorts = arg.get_short_and_visible_aliases().into_iter().flatten();
+    longs
+        .chain(arg.get_all_aliases().into_iter().flatten())
+        .map(|long| format!("--{long}"))
+        .chain(
+            shorts
+                .chain(arg.get_all_short_aliases().into_iter().flatten())
+                .map(|short| format!("-{short}")),
+        )
+        .collect()
+}
+
+/// The tokens of the occurrence of the option `arg` holding `value`.
+fn option_value(
+    command: &Command,
+    args: &[String],
+    arg: &Arg,
+    value: &str,
+) -> Option<std::ops::Range<usize>> {
+    let flags = flag_names(arg);
+    let max = arg.get_num_args().map_or(1, |range| range.max_values());
+    let mut at = 1;
+    while at < args.len() {
+        let token = &args[at];
+        let joined = flags
+            .iter()
+            .any(|flag| match token.strip_prefix(flag.as_str()) {
+                Some(rest) if flag.starts_with("--") => rest.strip_prefix('=') == Some(value),
+                Some(rest) => rest == value || rest.strip_prefix('=') == Some(value),
+                None => false,
+            });
+        if joined {
+            return Some(at..at + 1);
+        }
+        if flags.contains(token) {
+            let values = args[at + 1..]
+                .iter()
+                .take(max)
+                .take_while(|next| !next.starts_with('-') || *next == value)
+                .count();
+            if args[at + 1..at + 1 + values]
+                .iter()
+                .any(|next| next == value)
+            {
+                return Some(at..at + 1 + values);
+            }
+            at += values;
+        } else if separate_value(command, token) {
+            at += 1;
+        }
+        at += 1;
+    }
+    None
+}
+
+/// The token of the positional `value`, skipping the values of options.
+fn positional_value(
+    command: &Command,
+    args: &[String],
+    value: &str,
+) -> Option<std::ops::Range<usize>> {
+    let mut at = 1;
+    while at < args.len() {
+        if args[at] == value {
+            return Some(at..at + 1);
+        }
+        if separate_value(command, &args[at]) {
+            at += 1;
+        }
+        at += 1;
+    }
+    None
+}
+
+/// Whether the token is an option whose value is the next token.
+fn separate_value(command: &Command, token: &str) -> bool {
+    token.starts_with('-')
+        && !token.contains('=')
+        && find_arg(command, &|arg| {
+            flag_names(arg).iter().any(|flag| flag == token)
+        })
+        .is_some_and(|arg| arg.get_action().takes_values())
+}
+
+/// Make every arg of the command and its subcommands optional.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn relax(command: Command) -> Command {
+    let names: Vec<String> = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect();
+    names.iter().fold(
+        command.mut_args(|arg| arg.required(false)),
+        |command, name| command.mut_subcommand(name, relax),
+    )
+}
+
+/// Parse `T` with a changed command, formatting errors like clap's derive.
+fn try_parse_with<T, I, S>(mut command: Command, args: I) -> error::Result<T>
+where
+    T: clap::FromArgMatches,
+    I: IntoIterator<Item = S>,
+    S: Into<OsString> + Clone,
+{
+    let mut matches = command.try_get_matches_from_mut(args)?;
+    T::from_arg_matches_mut(&mut matches).map_err(|err| err.format(&mut command))
+}
+
+/// The args from a source of url args, translated for the command.
+fn source_args(command: &Command, source: &dyn WeArgsSource) -> error::Result<Vec<String>> {
+    let url_args = source.args().into_iter().map(OsString::from);
+    translate::url_to_args(command, url_args).map_err(|err| err.into_clap(command))
+}
+
+/// Parse `T` from web args, with the [help width](set_help_width).
+fn web_parse<T, I, S>(args: I) -> error::Result<T>
+where
+    T: Parser,
+    I: IntoIterator<Item = S>,
+    S: Into<OsString> + Clone,
+{
+    try_parse_with(help::sized(T::command()), args)
+}
+
+/// Exit for a short circuiting error with the parser's exit code.
+fn parser_exit<P: WeParser + ?Sized>(err: &error::Error) -> ! {
+    let code = P::we_exit_code(err.kind());
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        exit::native_exit_with(err, code)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        exit::web_exit_with(err, code)
+    }
+}
+
+/// Call the start function with parsed args, or report the error without
+/// exiting.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn start<T>(result: error::Result<T>, f: impl FnOnce(T)) {
+    match result {
+        Ok(opts) => f(opts),
+        Err(err) => output::report(&err),
+    }
+}
 
+/// The error to report for a web parse, every unexpected arg when
+/// [`Config::report_all_unexpected`] is set.
 #[cfg(target_arch = "wasm32")]
-use cliw::url_args::UrlArgs;
+fn web_error(command: &Command, args: &[String], err: error::Error) -> error::Error {
+    if config().report_all_unexpected {
+        unexpected::all_unexpected(command, args, err)
+    } else {
+        err
+    }
+}
 
 /// # Wrapper trait implemented for [`clap::Command`]
 ///
@@ -144,6 +695,36 @@ pub trait WeCommand {
     #[must_use]
     fn we_get_matches(self) -> ArgMatches;
 
+    /// # Get matches from a given source of url args
+    ///
+    /// Like [`we_get_matches`](WeCommand::we_get_matches()), but the args
+    /// are from `source` and translated like a url's, on native and the
+    /// web.  For host-side tests with canned args, or args from a cookie
+    /// or `postMessage`.  See [`WeArgsSource`].
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Exit
+    ///
+    /// This functon may call [`std::process::exit()`] after printing messages
+    /// if command line arguments are wrong or a help or version type argument
+    /// is given.  This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::{Arg, Command}; // Use clap to parse the arguments.
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let cli = Command::new("app").arg(Arg::new("name").long("name"));
+    ///
+    /// let matches = cli.we_get_matches_with_source(&vec!["/", "name=Jo"]);
+    /// assert_eq!(matches.get_one::<String>("name").unwrap(), "Jo");
+    /// ```
+    fn we_get_matches_with_source(self, source: &dyn WeArgsSource) -> ArgMatches;
+
     /// # Wrapper for [`clap::Command::get_matches_mut()`]
     ///
     /// Like [`we_get_matches`](crate::WeCommand::we_get_matches()) but
@@ -177,6 +758,33 @@ pub trait WeCommand {
     #[must_use]
     fn we_get_matches_mut(&mut self) -> ArgMatches;
 
+    /// # Get matches that know their arg source
+    ///
+    /// Like [`we_get_matches`](WeCommand::we_get_matches()), with the
+    /// matches wrapped in a [`WeMatches`] that derefs to the
+    /// [`ArgMatches`] and also tells the [`ArgSource`] the args were read
+    /// from on the web, for logging.
+    ///
+    /// # Exit
+    ///
+    /// Like [`we_get_matches`](WeCommand::we_get_matches()).
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::{Arg, Command};
+    /// use we_clap::WeCommand;
+    ///
+    /// let cli = Command::new("Native and Web Program").arg(Arg::new("name").long("name"));
+    ///
+    /// let matches = cli.we_get_matches_we();
+    /// let name = matches.get_one::<String>("name");
+    /// if let Some(source) = matches.source() {
+    ///     println!("{name:?} read from {source:?}");
+    /// }
+    /// ```
+    #[must_use]
+    fn we_get_matches_we(self) -> WeMatches;
+
     /// # Wrapper for [`clap::Command::try_get_matches()`]
     ///
     /// Gets command line arguments on native or the web.\
@@ -210,6 +818,106 @@ pub trait WeCommand {
     /// ```
     fn we_try_get_matches(self) -> error::Result<ArgMatches>;
 
+    /// # Wrapper for [`clap::Command::try_get_matches_from_mut()`]
+    ///
+    /// Like [`we_try_get_matches`](WeCommand::we_try_get_matches()), but
+    /// the command is borrowed, not consumed, so it can parse again, in a
+    /// REPL loop for example.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Errors
+    ///
+    /// If help or version type arguments are entered than [`clap::error`]
+    /// will be returned instead of [`clap::ArgMatches`].
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("Native and Web Program");
+    ///
+    /// // use WeCommand function instead of clap try_get_matches_mut
+    /// match cli.we_try_get_matches_mut() {
+    ///     Ok(matches) => {} // handle matches
+    ///     Err(err) => {} // handle error
+    /// }
+    /// // cli is still usable
+    /// ```
+    fn we_try_get_matches_mut(&mut self) -> error::Result<ArgMatches>;
+
+    /// # Wrapper for [`clap::Command::get_matches_from()`]
+    ///
+    /// Parses the given args on native or the web, instead of the process
+    /// args or the url.  For deterministic tests and for a REPL.  The args
+    /// are passed to clap unchanged on both, give them as on a command line,
+    /// program name first.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Exit
+    ///
+    /// Like [`we_get_matches`](crate::WeCommand::we_get_matches()), may exit
+    /// after printing messages on native or the web if the args are wrong or
+    /// a help or version type argument is given.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::{Arg, Command}; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let cli = Command::new("app").arg(Arg::new("name").long("name"));
+    ///
+    /// let matches = cli.we_get_matches_from(["app", "--name", "bob"]);
+    /// assert_eq!(matches.get_one::<String>("name").unwrap(), "bob");
+    /// ```
+    #[must_use]
+    fn we_get_matches_from<I, T>(self, itr: I) -> ArgMatches
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone;
+
+    /// # Wrapper for [`clap::Command::try_get_matches_from()`]
+    ///
+    /// Parses the given args on native or the web, instead of the process
+    /// args or the url.  The args are passed to clap unchanged on both.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Errors
+    ///
+    /// If help or version type arguments are entered than [`clap::error`]
+    /// will be returned instead of [`clap::ArgMatches`].
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let cli = Command::new("app");
+    ///
+    /// assert!(cli.we_try_get_matches_from(["app", "--nope"]).is_err());
+    /// ```
+    fn we_try_get_matches_from<I, T>(self, itr: I) -> error::Result<ArgMatches>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone;
+
     /// # Print help message
     /// Prints a help message on native or the web.\
     /// Native output is to stdout/stderr.\
@@ -255,131 +963,1545 @@ pub trait WeCommand {
     /// let result = cli.we_print_long_help();
     /// ```
     fn we_print_long_help(&mut self) -> std::io::Result<()>;
-}
-
-impl WeCommand for Command {
-    fn we_get_matches(self) -> ArgMatches {
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.get_matches()
-        }
-        #[cfg(target_arch = "wasm32")]
-        {
-            let command = self.try_get_matches_from(UrlArgs::new());
-            match command {
-                Ok(command) => command,
-                Err(err) => {
-                    let msg = format!("{err}");
-                    match err.kind() {
-                        ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => {
-                            cliw::output::print(&msg);
-                        }
-                        _ => {
-                            cliw::output::eprint(&msg);
-                        }
-                    }
-                    std::process::exit(0); // Exit code meaningless on wasm.
-                }
-            }
-        }
-    }
-    fn we_get_matches_mut(&mut self) -> ArgMatches {
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.get_matches_mut()
-        }
-        #[cfg(target_arch = "wasm32")]
-        {
-            let command = self.try_get_matches_from_mut(UrlArgs::new());
-            match command {
-                Ok(command) => command,
-                Err(err) => {
-                    let msg = format!("{err}");
-                    match err.kind() {
-                        ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => {
-                            cliw::output::print(&msg);
-                        }
-                        _ => {
-                            cliw::output::eprint(&msg);
-                        }
-                    }
-                    std::process::exit(0); // Exit code meaningless on wasm.
-                }
-            }
-        }
-    }
-    fn we_try_get_matches(self) -> error::Result<ArgMatches> {
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.try_get_matches()
-        }
-        #[cfg(target_arch = "wasm32")]
-        {
-            self.try_get_matches_from(UrlArgs::new())
-        }
-    }
 
-    fn we_print_help(&mut self) -> std::io::Result<()> {
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.print_help()
-        }
+    /// # Render help message
+    /// The help message as plain text, without printing it.  For apps that
+    /// show help in their own panel or page element.  The same on native
+    /// and the web.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("demo").about("A demo");
+    ///
+    /// let help = cli.we_render_help();
+    /// assert!(help.contains("Usage: demo"));
+    /// ```
+    fn we_render_help(&mut self) -> String;
 
-        #[cfg(target_arch = "wasm32")]
-        {
-            let styled = &self.render_help();
-            cliw::output::print(&format!("{styled}"));
-            Ok(())
-        }
-    }
+    /// # Render long help message
+    /// The long help message as plain text, without printing it.  The same
+    /// on native and the web.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("demo").long_about("A longer demo");
+    ///
+    /// let help = cli.we_render_long_help();
+    /// assert!(help.contains("A longer demo"));
+    /// ```
+    fn we_render_long_help(&mut self) -> String;
 
-    fn we_print_long_help(&mut self) -> std::io::Result<()> {
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.print_long_help()
+    /// # Render help as HTML
+    /// The help message as a semantic HTML fragment instead of text, for a
+    /// docs site or page styled with its own CSS.  A `<div
+    /// class="we-clap-help">` holds the about text, the usage line, and a
+    /// table of args under each heading, `Arguments`, `Options` and any
+    /// [`help_heading`](clap::Arg::help_heading), then a table of
+    /// subcommands.  Value names, defaults and possible values are shown,
+    /// hidden args are not.  All text is escaped.  The same on native and
+    /// the web.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::{Arg, Command}; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let cli = Command::new("demo").arg(Arg::new("name").long("name"));
+    ///
+    /// let html = cli.we_render_help_html();
+    /// assert!(html.contains("<code>--name &lt;name&gt;</code>"));
+    /// ```
+    fn we_render_help_html(&self) -> String;
+
+    /// # Write help message
+    /// Writes the help message as plain text to `w`, a file or a buffer,
+    /// instead of the console or stdout.  The same on native and the web,
+    /// laid out for the [help width](set_help_width) on the web.
+    ///
+    /// # Errors
+    /// The [`std::io::Error`] of the writer, from
+    /// [`clap::Command::write_help()`].
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("demo").about("A demo");
+    ///
+    /// let mut help = Vec::new();
+    /// cli.we_write_help(&mut help).unwrap();
+    /// assert!(String::from_utf8(help).unwrap().contains("Usage: demo"));
+    /// ```
+    fn we_write_help<W: std::io::Write>(&mut self, w: &mut W) -> std::io::Result<()>;
+
+    /// # Write long help message
+    /// Writes the long help message as plain text to `w`, like
+    /// [`we_write_help`](WeCommand::we_write_help()).
+    ///
+    /// # Errors
+    /// The [`std::io::Error`] of the writer, from
+    /// [`clap::Command::write_long_help()`].
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("demo").long_about("A longer demo");
+    ///
+    /// let mut help = Vec::new();
+    /// cli.we_write_long_help(&mut help).unwrap();
+    /// assert!(String::from_utf8(help).unwrap().contains("A longer demo"));
+    /// ```
+    fn we_write_long_help<W: std::io::Write>(&mut self, w: &mut W) -> std::io::Result<()>;
+
+    /// # Print version message
+    /// Prints the version like `--version` does, the name and version from
+    /// [`clap::Command::render_version()`], on native or the web.  For an
+    /// "About" dialog or menu item.\
+    /// Native output is to stdout.\
+    /// Web output is to console or popup alert
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("demo").version("1.2.3");
+    ///
+    /// cli.we_print_version(); // demo 1.2.3
+    /// ```
+    fn we_print_version(&mut self);
+
+    /// # Print long version message
+    /// Prints the long version like `--version` does with a `long_version`,
+    /// from [`clap::Command::render_long_version()`], on native or the
+    /// web.\
+    /// Native output is to stdout.\
+    /// Web output is to console or popup alert
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("demo").long_version("1.2.3\nbuilt today");
+    ///
+    /// cli.we_print_long_version();
+    /// ```
+    fn we_print_long_version(&mut self);
+
+    /// # Print help message into a page element
+    /// Sets the help message as the text of the element with the id, like a
+    /// `<pre id="cli-output">`, on the web.  With no such element the help
+    /// goes to the web output, after a console warning.  Needs the
+    /// "web-help-element" feature, without it and on native this is the
+    /// same as [`we_print_help`](WeCommand::we_print_help()).
+    ///
+    /// For every message, errors too, use
+    /// [`OutputTarget::Element`] instead.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("Native and Web Program");
+    ///
+    /// cli.we_print_help_to_element("cli-output");
+    /// ```
+    fn we_print_help_to_element(&mut self, id: &str);
+
+    /// # Check the command for configuration errors
+    ///
+    /// Runs clap's [`Command::debug_assert()`] on a clone of the command,
+    /// which panics on a misconfigured command, like two args with the same
+    /// long flag.  Call it in a test, a `wasm-bindgen-test` case on the web,
+    /// to catch a broken command before it ships.
+    ///
+    /// The web parse functions, like
+    /// [`we_get_matches`](WeCommand::we_get_matches()), run the same checks
+    /// on wasm, clap makes them when it parses.  Like clap's they only run in
+    /// debug builds, this does nothing in a release build.  Add the
+    /// "web-panic-hook" feature to see the message of a failed check on the
+    /// web.
+    ///
+    /// # Panics
+    ///
+    /// On a misconfigured command, in debug builds.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::{Arg, Command};
+    /// use we_clap::WeCommand;
+    ///
+    /// let cli = Command::new("app").arg(Arg::new("name").long("name"));
+    /// cli.we_debug_assert();
+    /// ```
+    fn we_debug_assert(&self);
+
+    /// # Print help message with custom indentation
+    /// Prints a help message on native or the web with the args indented
+    /// by `indent` spaces instead of clap's two.  Use `0` to save
+    /// horizontal space on narrow phone screens.\
+    /// Native output is to stdout.\
+    /// Web output is to console or popup alert
+    ///
+    /// # Errors
+    /// Ouput errors are ignored.  Ok(()) is always returned
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("Native and Web Program");
+    ///
+    /// let result = cli.we_print_help_compact(0);
+    /// ```
+    fn we_print_help_compact(&mut self, indent: usize) -> std::io::Result<()>;
+
+    /// # Print help message with help text on the next line
+    /// Turns clap's `next_line_help` on or off, then prints the help message
+    /// on native or the web.  With it on the help text of each arg goes on
+    /// the line below the arg, which reads better in narrow web containers.
+    /// The setting stays on the command.\
+    /// Native output is to stdout.\
+    /// Web output is to console or popup alert
+    ///
+    /// # Errors
+    /// Same as [`we_print_help`](crate::WeCommand::we_print_help()).
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("Native and Web Program");
+    ///
+    /// let result = cli.we_print_help_next_line(true);
+    /// ```
+    fn we_print_help_next_line(&mut self, enabled: bool) -> std::io::Result<()>;
+
+    /// # Check for a help or version request
+    ///
+    /// A cheap scan of the command line arguments on native or the web for
+    /// help or version flags, without parsing or rendering anything.  Lets
+    /// you skip expensive setup when the program is only going to show help
+    /// or version and exit.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// Finds clap's default help and version flags, unless disabled, any args
+    /// with a help or version action, and the `help` subcommand.  Only the
+    /// top level command's flags are known.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("Native and Web Program");
+    ///
+    /// if cli.we_wants_help_or_version().is_none() {
+    ///     // expensive setup
+    /// }
+    /// ```
+    #[must_use]
+    fn we_wants_help_or_version(&self) -> Option<HelpOrVersion>;
+
+    /// # List subcommands for a web menu
+    ///
+    /// The name, about text and whether it has subcommands of its own, for
+    /// every subcommand that isn't hidden.  Use it to generate a navigation
+    /// menu of the available tools.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let cli = Command::new("tools").subcommand(Command::new("convert"));
+    ///
+    /// for tool in cli.we_subcommands() {
+    ///     println!("{}", tool.name);
+    /// }
+    /// ```
+    #[must_use]
+    fn we_subcommands(&self) -> Vec<SubcommandInfo>;
+
+    /// # Render help once for repeated display
+    /// Renders the help, long help and usage into a [`HelpCache`].
+    /// Displaying help from the cache doesn't render it again.
+    ///
+    /// Create a new cache if the command changes.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("Native and Web Program");
+    ///
+    /// let cache = cli.we_cache_help();
+    /// cache.print_help();
+    /// ```
+    #[must_use]
+    fn we_cache_help(&mut self) -> HelpCache;
+
+    /// # Show the usage as a textarea placeholder
+    /// Sets the `placeholder` of the element with id `textarea_id` to a
+    /// compact usage string, to guide users composing a command in a
+    /// command builder UI.  An `input` element gets the placeholder too.
+    /// Any other element, like a hint next to the textarea, gets the usage
+    /// as its text instead.
+    ///
+    /// Returns `false` when there is no such element.  Needs the
+    /// "web-placeholder" feature on the web, without it and on native
+    /// nothing is set and `false` is returned.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Command; // Use clap to parse the arguments
+    /// use we_clap::WeCommand; // Use we_clap to provide the arguments.
+    ///
+    /// let mut cli = Command::new("builder");
+    ///
+    /// let placed = cli.we_help_into_placeholder("command-input");
+    /// ```
+    fn we_help_into_placeholder(&mut self, textarea_id: &str) -> bool;
+}
+
+impl WeCommand for Command {
+    fn we_get_matches(self) -> ArgMatches {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.try_get_matches()
+                .unwrap_or_else(|err| native_exit(&err))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let mut cli = help::sized(self);
+            let args = translate::web_args(&cli).unwrap_or_else(|err| web_exit(&err));
+            let command = perf::timed(perf::PARSE, || cli.try_get_matches_from_mut(&args));
+            match command {
+                Ok(command) => command,
+                Err(err) => web_exit(&web_error(&cli, &args, err)),
+            }
+        }
+    }
+    fn we_get_matches_mut(&mut self) -> ArgMatches {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.try_get_matches_from_mut(std::env::args_os())
+                .unwrap_or_else(|err| native_exit(&err))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            help::size(self);
+            let args = translate::web_args(self).unwrap_or_else(|err| web_exit(&err));
+            let command = perf::timed(perf::PARSE, || self.try_get_matches_from_mut(&args));
+            match command {
+                Ok(command) => command,
+                Err(err) => web_exit(&web_error(self, &args, err)),
+            }
+        }
+    }
+
+    fn we_get_matches_we(self) -> WeMatches {
+        WeMatches::new(self.we_get_matches())
+    }
+
+    fn we_get_matches_with_source(self, source: &dyn WeArgsSource) -> ArgMatches {
+        let args = source_args(&self, source).and_then(|args| self.try_get_matches_from(args));
+        match args {
+            Ok(matches) => matches,
+            #[cfg(not(target_arch = "wasm32"))]
+            Err(err) => native_exit(&err),
+            #[cfg(target_arch = "wasm32")]
+            Err(err) => web_exit(&err),
+        }
+    }
+
+    fn we_try_get_matches(self) -> error::Result<ArgMatches> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.try_get_matches()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let command = help::sized(self);
+            let args = translate::web_args(&command)?;
+            perf::timed(perf::PARSE, || command.try_get_matches_from(args))
+        }
+    }
+
+    fn we_try_get_matches_mut(&mut self) -> error::Result<ArgMatches> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.try_get_matches_from_mut(std::env::args_os())
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            help::size(self);
+            let args = translate::web_args(self)?;
+            perf::timed(perf::PARSE, || self.try_get_matches_from_mut(args))
+        }
+    }
+
+    fn we_get_matches_from<I, T>(self, itr: I) -> ArgMatches
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        match self.try_get_matches_from(itr) {
+            Ok(matches) => matches,
+            #[cfg(not(target_arch = "wasm32"))]
+            Err(err) => native_exit(&err),
+            #[cfg(target_arch = "wasm32")]
+            Err(err) => web_exit(&err),
+        }
+    }
+    fn we_try_get_matches_from<I, T>(self, itr: I) -> error::Result<ArgMatches>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        self.try_get_matches_from(itr)
+    }
+
+    fn we_print_help(&mut self) -> std::io::Result<()> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.print_help()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            help::size(self);
+            let styled = &self.render_help();
+            output::print(&output::styled(styled));
+            #[cfg(feature = "web-console-groups")]
+            help::print_groups(self, false);
+            Ok(())
+        }
+    }
+
+    fn we_print_long_help(&mut self) -> std::io::Result<()> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.print_long_help()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            help::size(self);
+            let styled = &self.render_long_help();
+            output::print(&output::styled(styled));
+            #[cfg(feature = "web-console-groups")]
+            help::print_groups(self, true);
+            Ok(())
+        }
+    }
+
+    fn we_render_help(&mut self) -> String {
+        self.render_help().to_string()
+    }
+
+    fn we_render_long_help(&mut self) -> String {
+        self.render_long_help().to_string()
+    }
+
+    fn we_render_help_html(&self) -> String {
+        help_html::render(self)
+    }
+
+    fn we_write_help<W: std::io::Write>(&mut self, w: &mut W) -> std::io::Result<()> {
+        help::size(self);
+        self.write_help(w)
+    }
+
+    fn we_write_long_help<W: std::io::Write>(&mut self, w: &mut W) -> std::io::Result<()> {
+        help::size(self);
+        self.write_long_help(w)
+    }
+
+    fn we_print_version(&mut self) {
+        output::print(&self.render_version());
+    }
+
+    fn we_print_long_version(&mut self) {
+        output::print(&self.render_long_version());
+    }
+
+    fn we_print_help_to_element(&mut self, id: &str) {
+        #[cfg(all(target_arch = "wasm32", feature = "web-help-element"))]
+        help::show_in(id, &self.render_help().to_string());
+        #[cfg(not(all(target_arch = "wasm32", feature = "web-help-element")))]
+        {
+            let _ = id;
+            let _ = self.we_print_help();
+        }
+    }
+
+    fn we_debug_assert(&self) {
+        self.clone().debug_assert();
+    }
+
+    fn we_print_help_compact(&mut self, indent: usize) -> std::io::Result<()> {
+        help::size(self);
+        let styled = &self.render_help();
+        output::print(&help::reindent(&format!("{styled}"), indent));
+        Ok(())
+    }
+
+    fn we_print_help_next_line(&mut self, enabled: bool) -> std::io::Result<()> {
+        help::set_next_line(self, enabled);
+        self.we_print_help()
+    }
+
+    fn we_wants_help_or_version(&self) -> Option<HelpOrVersion> {
+        let args = translate::args(self).ok()?;
+        help::wants_help_or_version(self, &args)
+    }
+
+    fn we_subcommands(&self) -> Vec<SubcommandInfo> {
+        menu::subcommands(self)
+    }
+
+    fn we_cache_help(&mut self) -> HelpCache {
+        help::size(self);
+        HelpCache::new(self)
+    }
+
+    fn we_help_into_placeholder(&mut self, textarea_id: &str) -> bool {
+        #[cfg(all(target_arch = "wasm32", feature = "web-placeholder"))]
+        {
+            help::into_placeholder(textarea_id, &help::placeholder(self))
+        }
+        #[cfg(not(all(target_arch = "wasm32", feature = "web-placeholder")))]
+        {
+            let _ = textarea_id;
+            false
+        }
+    }
+}
+
+/// # Wrapper trait for [`clap::Parser`]
+///
+/// Functions to parse your Opt with clap on native or the web.\
+/// On native the arguments come from [`std::env::ArgsOs`].\
+/// On the web the arguments come from [`cliw::url_args::UrlArgs`].
+///
+/// Use these instead of their clap counterparts to make your command
+/// line program work on native or the web.
+///
+/// # Example
+/// ``` rust
+/// use clap::Parser; // Use clap to parse the arguments
+/// use we_clap::WeParser; // Use we_clap to provide the arguments.
+///
+/// #[derive(Parser, Debug, Default)]
+/// #[command(author, version, about, long_about)]
+/// pub struct Opts {}
+///
+/// // Implement web enabled parser for your struct
+/// impl we_clap::WeParser for Opts {}
+///
+/// // Use we_parse and it works on native or web.
+/// // Type annotations needed.
+/// let opts: Opts = Opts::we_parse();
+/// ```
+pub trait WeParser {
+    /// # Exit code for a short circuiting error
+    ///
+    /// The process exit code used when help, version or an error stops
+    /// parsing, on native or the web.  On the web the code only matters
+    /// under WASI or a Node harness, where it can fail a CI run.
+    ///
+    /// The default is [`we_exit_code()`], the code from
+    /// [`Config::exit_codes`] or clap's own: `0` for help and version, `2`
+    /// for errors.  Override it to choose per [`error::ErrorKind`].
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::error::ErrorKind;
+    /// use clap::Parser;
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {
+    ///     fn we_exit_code(kind: ErrorKind) -> i32 {
+    ///         match kind {
+    ///             ErrorKind::MissingRequiredArgument => 3,
+    ///             kind => we_clap::we_exit_code(kind),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    fn we_exit_code(kind: error::ErrorKind) -> i32 {
+        we_exit_code(kind)
+    }
+
+    /// # Wrapper for [`clap::Parser::parse()`]
+    ///
+    /// Gets command line arguments on native or the web.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Exit
+    ///
+    /// This functon may call [`std::process::exit()`] after printing messages if
+    /// command line arguments are wrong or a help or version type argument is given.
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments to clap.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// #[command(author, version, about, long_about)]
+    /// pub struct Opts {}
+    ///
+    /// // Implement web enabled parser for your struct
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// // use we_parse and it works on native or web.
+    /// // Type annotations needed
+    /// let opts: Opts = Opts::we_parse();
+    /// ```
+    #[must_use]
+    fn we_parse<T>() -> T
+    where
+        T: Parser,
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Parser::try_parse().unwrap_or_else(|err| parser_exit::<Self>(&err))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let command = T::command();
+            let args =
+                translate::web_args(&command).unwrap_or_else(|err| parser_exit::<Self>(&err));
+            let opts = perf::timed(perf::PARSE, || web_parse(&args));
+            match opts {
+                Ok(opts) => opts,
+                Err(err) => parser_exit::<Self>(&web_error(&command, &args, err)),
+            }
+        }
+    }
+    /// # Wrapper for [`clap::Parser::try_parse()`]
+    ///
+    /// Gets command line arguments on native or the web.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Errors
+    ///
+    /// If help or version type arguments are entered than [`clap::error`]
+    /// will be returned.  This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// #[command(author, version, about, long_about)]
+    /// pub struct Opts {}
+    ///
+    /// // Implement web enabled parser for your struct
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// // use we_parse and it works on native or web.
+    /// // Type annotations needed
+    /// match Opts::we_try_parse::<Opts>() {
+    ///     Ok(opts) => {} // handle opts
+    ///     Err(err) => {} // handle error
+    /// }
+    fn we_try_parse<T>() -> Result<T, error::Error>
+    where
+        T: Parser,
+    {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let args = translate::web_args(&T::command())?;
+            perf::timed(perf::PARSE, || web_parse(args))
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Parser::try_parse()
+        }
+    }
+
+    /// # Parse from a given source of url args
+    ///
+    /// Like [`we_parse`](WeParser::we_parse()), but the args are from
+    /// `source` and translated like a url's, on native and the web.  For
+    /// host-side tests with canned args, or args from a cookie or
+    /// `postMessage`.  See [`WeArgsSource`].
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Exit
+    ///
+    /// This functon may call [`std::process::exit()`] after printing messages if
+    /// command line arguments are wrong or a help or version type argument is given.
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug)]
+    /// pub struct Opts {
+    ///     #[arg(short, long)]
+    ///     verbose: bool,
+    /// }
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// let opts: Opts = Opts::we_parse_with_source(&vec!["/", "-v"]);
+    /// assert!(opts.verbose);
+    /// ```
+    #[must_use]
+    fn we_parse_with_source<T>(source: &dyn WeArgsSource) -> T
+    where
+        T: Parser,
+    {
+        let command = T::command();
+        match source_args(&command, source).and_then(web_parse) {
+            Ok(opts) => opts,
+            Err(err) => parser_exit::<Self>(&err),
+        }
+    }
+
+    /// # Try to parse, with the error rendered
+    ///
+    /// Like [`we_try_parse`](WeParser::we_try_parse()), but the error is a
+    /// [`RenderedError`], with the message as plain text and whether it is
+    /// help, version or a failure, so the app can log the kind and show the
+    /// message without classifying it again.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// # Errors
+    ///
+    /// A [`RenderedError`] when parsing fails, or for help or version.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// match Opts::we_try_parse_rendered::<Opts>() {
+    ///     Ok(opts) => println!("{opts:?}"),
+    ///     Err(err) => println!("{:?}: {}", err.kind, err.rendered),
+    /// }
+    /// ```
+    fn we_try_parse_rendered<T>() -> Result<T, RenderedError>
+    where
+        T: Parser,
+    {
+        Self::we_try_parse::<T>().map_err(RenderedError::from)
+    }
+
+    /// # Parse without exiting or printing
+    ///
+    /// Gets command line arguments on native or the web, like
+    /// [`we_try_parse`](WeParser::we_try_parse()), and returns a
+    /// [`WeOutcome`].  Help and version come back as text for the app to
+    /// show, in its own panel for example.  Nothing is printed and the
+    /// process never exits, for long lived web apps and game loops.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::{WeOutcome, WeParser}; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// #[command(author, version, about, long_about)]
+    /// pub struct Opts {}
+    ///
+    /// // Implement web enabled parser for your struct
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// match Opts::we_parse_outcome::<Opts>() {
+    ///     WeOutcome::Parsed(opts) => {} // run with opts
+    ///     WeOutcome::Help(text) | WeOutcome::Version(text) => {} // show text
+    ///     WeOutcome::Error(err) => {} // show err
+    /// }
+    /// ```
+    fn we_parse_outcome<T>() -> WeOutcome<T>
+    where
+        T: Parser,
+    {
+        Self::we_try_parse::<T>().into()
+    }
+
+    /// # Parse, or hand the text to show to a sink
+    ///
+    /// Gets command line arguments on native or the web and returns the
+    /// parsed `T`.  For help, version or an error the rendered plain text
+    /// goes to `on_help` and `None` is returned, for apps like `bevy` or
+    /// `eframe` whose main loop owns the window and shows the text in its
+    /// own UI.  Nothing is printed, no alert pops up and the process never
+    /// exits.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// Use [`we_parse_outcome`](WeParser::we_parse_outcome()) to tell help
+    /// from an error.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// let mut banner = String::new();
+    /// let opts: Option<Opts> = Opts::we_parse_or(|text| banner = text);
+    /// // run with opts, or show the banner in the UI
+    /// ```
+    fn we_parse_or<T>(mut on_help: impl FnMut(String)) -> Option<T>
+    where
+        T: Parser,
+    {
+        Self::we_try_parse_rendered::<T>()
+            .map_err(|err| on_help(err.rendered))
+            .ok()
+    }
+
+    /// # Wrapper for [`clap::Parser::parse_from()`]
+    ///
+    /// Parses the given args on native or the web, instead of the process
+    /// args or the url.  For unit tests of your command line logic and for
+    /// a REPL.  The args are passed to clap unchanged on both, there is no
+    /// url translation, so give them as on a command line, program name
+    /// first.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Exit
+    ///
+    /// Like [`we_parse`](crate::WeParser::we_parse()), may exit after
+    /// printing messages on native or the web if the args are wrong or a
+    /// help or version type argument is given.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments to clap.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {
+    ///     #[arg(long)]
+    ///     name: Option<String>,
+    /// }
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// let opts: Opts = Opts::we_parse_from(["app", "--name", "bob"]);
+    /// assert_eq!(opts.name.as_deref(), Some("bob"));
+    /// ```
+    #[must_use]
+    fn we_parse_from<T, I, S>(itr: I) -> T
+    where
+        T: Parser,
+        I: IntoIterator<Item = S>,
+        S: Into<OsString> + Clone,
+    {
+        match T::try_parse_from(itr) {
+            Ok(opts) => opts,
+            Err(err) => parser_exit::<Self>(&err),
+        }
+    }
+
+    /// # Wrapper for [`clap::Parser::try_parse_from()`]
+    ///
+    /// Parses the given args on native or the web, instead of the process
+    /// args or the url.  The args are passed to clap unchanged on both.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Errors
+    ///
+    /// If help or version type arguments are entered than [`clap::error`]
+    /// will be returned.  This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// assert!(Opts::we_try_parse_from::<Opts, _, _>(["app", "--nope"]).is_err());
+    /// ```
+    fn we_try_parse_from<T, I, S>(itr: I) -> Result<T, error::Error>
+    where
+        T: Parser,
+        I: IntoIterator<Item = S>,
+        S: Into<OsString> + Clone,
+    {
+        T::try_parse_from(itr)
+    }
+
+    /// # Wrapper for [`clap::Parser::update_from()`]
+    ///
+    /// Updates the parsed struct in place from the command line arguments
+    /// on native or the web, for incremental config in a running app.
+    /// Only the args present change their fields, everything else keeps
+    /// its value, defaults are not applied again.  On the web read the url
+    /// again after a `hashchange` or `popstate`, with the
+    /// [`ArgSource`] of your choice, and call this to patch only what the
+    /// new url gives.  A subcommand in the args replaces or updates the
+    /// current one, as clap does.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Exit
+    ///
+    /// On an error or a help or version request, like
+    /// [`we_parse`](WeParser::we_parse()).  The struct is left as it was.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {
+    ///     #[arg(long)]
+    ///     pub name: Option<String>,
+    /// }
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// let mut opts = Opts::default();
+    /// opts.we_update();
+    /// ```
+    fn we_update(&mut self)
+    where
+        Self: Parser + Sized,
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.try_update_from(std::env::args_os())
+                .unwrap_or_else(|err| parser_exit::<Self>(&err));
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let command = Self::command();
+            let args =
+                translate::web_args(&command).unwrap_or_else(|err| parser_exit::<Self>(&err));
+            if let Err(err) = perf::timed(perf::PARSE, || self.try_update_from(&args)) {
+                parser_exit::<Self>(&web_error(&command, &args, err));
+            }
+        }
+    }
+
+    /// # Wrapper for [`clap::Parser::update_from()`]
+    ///
+    /// Updates the parsed struct in place from the given args on native or
+    /// the web, like [`we_update`](WeParser::we_update()).  The args are
+    /// passed to clap unchanged, program name first.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Exit
+    ///
+    /// On an error or a help or version request, like
+    /// [`we_parse`](WeParser::we_parse()).
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {
+    ///     #[arg(long)]
+    ///     pub name: Option<String>,
+    ///     #[arg(long, default_value_t = 1)]
+    ///     pub level: u8,
+    /// }
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// let mut opts = Opts::we_parse_from::<Opts, _, _>(["app", "--name", "bob"]);
+    /// opts.we_update_from(["app", "--level", "3"]);
+    /// assert_eq!(opts.name.as_deref(), Some("bob"));
+    /// assert_eq!(opts.level, 3);
+    /// ```
+    fn we_update_from<I, S>(&mut self, itr: I)
+    where
+        Self: Parser + Sized,
+        I: IntoIterator<Item = S>,
+        S: Into<OsString> + Clone,
+    {
+        self.try_update_from(itr)
+            .unwrap_or_else(|err| parser_exit::<Self>(&err));
+    }
+
+    /// # Parse args from the [`document.referrer`] on the web
+    ///
+    /// Like [`we_parse`](crate::WeParser::we_parse()) but on the web the
+    /// arguments come from the query string of the page that linked to this
+    /// one.  Lets an embedded widget adapt to the page it was opened from.\
+    /// Native args are from [`std::env::ArgsOs`], the same as `we_parse`.
+    ///
+    /// A missing or empty referrer gives no arguments.  Browsers usually
+    /// strip the query string from cross-origin referrers, which also gives
+    /// no arguments.
+    ///
+    /// Requires the "web-referrer" feature.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Exit
+    ///
+    /// This functon may call [`std::process::exit()`] after printing messages if
+    /// command line arguments are wrong or a help or version type argument is given.
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments to clap.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// // Type annotations needed
+    /// let opts: Opts = Opts::we_parse_from_referrer();
+    /// ```
+    ///
+    /// [`document.referrer`]: https://developer.mozilla.org/en-US/docs/Web/API/Document/referrer
+    #[cfg(feature = "web-referrer")]
+    #[must_use]
+    fn we_parse_from_referrer<T>() -> T
+    where
+        T: Parser,
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Parser::try_parse().unwrap_or_else(|err| parser_exit::<Self>(&err))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            match translate::referrer_args(&T::command()).and_then(web_parse) {
+                Ok(opts) => opts,
+                Err(err) => parser_exit::<Self>(&err),
+            }
+        }
+    }
+
+    /// # Parse args from a `#[wasm_bindgen(start)]` function
+    ///
+    /// Parses the command line arguments on native or the web and calls `f`
+    /// with the result.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// On the web help, version and error messages are sent to the console or
+    /// popup alert and `f` is not called.  Unlike
+    /// [`we_parse`](crate::WeParser::we_parse()) this does not call
+    /// [`std::process::exit()`], the start function simply returns and
+    /// the module stays usable.
+    ///
+    /// On native this behaves like `we_parse`, clap prints and exits.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust,ignore
+    /// use clap::Parser;
+    /// use wasm_bindgen::prelude::*;
+    /// use we_clap::WeParser;
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// #[wasm_bindgen(start)]
+    /// fn start() {
+    ///     Opts::we_start_parse(|opts: Opts| {
+    ///         // run the app with opts
+    ///     });
+    /// }
+    /// ```
+    fn we_start_parse<T>(f: impl FnOnce(T))
+    where
+        T: Parser,
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            f(Parser::try_parse().unwrap_or_else(|err| parser_exit::<Self>(&err)));
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            start(translate::web_args(&T::command()).and_then(web_parse), f);
+        }
+    }
+
+    /// # Parse args from an async source that can be aborted
+    ///
+    /// Awaits `source` for the command line arguments, then parses them.
+    /// If `abort` resolves first the source is dropped, which cancels it,
+    /// and [`WeError::Aborted`] is returned.  Nothing is printed and the
+    /// process never exits.
+    ///
+    /// The arguments from `source` are given to clap as they are, the first
+    /// one is the binary name.
+    ///
+    /// On the web use [`abort_signal`] to abort with the [`AbortSignal`]
+    /// of an [`AbortController`], the same one that cancels your `fetch`.
+    /// Once the signal is aborted parsing never completes.
+    ///
+    /// # Errors
+    ///
+    /// [`WeError::Aborted`] if aborted, otherwise [`WeError::Clap`] if clap
+    /// fails to parse or help or version type arguments are given.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// async fn load() -> Result<Opts, we_clap::WeError> {
+    ///     let source = async { vec!["app"] }; // fetch the args
+    ///     let abort = std::future::pending(); // or we_clap::abort_signal(&signal)
+    ///     Opts::we_parse_async_abortable(source, abort).await
+    /// }
+    /// ```
+    ///
+    /// [`AbortController`]: https://developer.mozilla.org/en-US/docs/Web/API/AbortController
+    /// [`AbortSignal`]: https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal
+    /// [`abort_signal`]: https://docs.rs/we_clap/latest/we_clap/fn.abort_signal.html
+    fn we_parse_async_abortable<T, I, S>(
+        source: impl Future<Output = I>,
+        abort: impl Future<Output = ()>,
+    ) -> impl Future<Output = Result<T, WeError>>
+    where
+        T: Parser,
+        I: IntoIterator<Item = S>,
+        S: Into<OsString> + Clone,
+    {
+        async move {
+            match future::Abortable::new(source, abort).await {
+                Some(args) => Ok(T::try_parse_from(args)?),
+                None => Err(WeError::Aborted),
+            }
+        }
+    }
+
+    /// # Parse args that may arrive later
+    ///
+    /// Like [`we_try_parse`](crate::WeParser::we_try_parse()), but async,
+    /// for web apps driven by an async main.  On the web it awaits the
+    /// source set with [`set_async_arg_source`], or with
+    /// [`ArgSource::PostMessage`] waits for the first message of args from
+    /// the parent frame, or the timeout, then parses.  Otherwise it parses
+    /// the native or web args right away.
+    ///
+    /// The message data is a query string, `"verbose&name=Jo"`, or an array
+    /// of query strings, `["verbose", "name=Jo"]`, translated like a url's.
+    /// Without a message within the timeout the query string is read.
+    ///
+    /// Nothing is printed and the process never exits.  Await it from
+    /// `wasm_bindgen_futures::spawn_local` or any other executor.
+    ///
+    /// # Errors
+    ///
+    /// [`WeError::Clap`] if clap fails to parse or help or version type
+    /// arguments are given.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::{ArgSource, WeError, WeParser}; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// async fn load() -> Result<Opts, WeError> {
+    ///     we_clap::set_arg_source(ArgSource::PostMessage { timeout_ms: 2000 });
+    ///     Opts::we_parse_async().await
+    /// }
+    /// ```
+    fn we_parse_async<T>() -> impl Future<Output = Result<T, WeError>>
+    where
+        T: Parser,
+    {
+        async move {
+            let command = T::command();
+            let args = match source::async_args().await {
+                Some(posted) => source_args(&command, &posted)?
+                    .into_iter()
+                    .map(OsString::from)
+                    .collect(),
+                None => translate::args(&command)?,
+            };
+            Ok(web_parse(args)?)
+        }
+    }
+
+    /// # Parse with a config file named by an arg
+    ///
+    /// Gets the command line arguments on native or the web, then reads
+    /// the config file named by the `config_arg` arg, like `--config`, and
+    /// layers its values under the explicit args.  An arg given on the
+    /// command line or url wins over the config file, which wins over the
+    /// clap default.  Without the arg the args are parsed as they are.\
+    /// Native args are from [`std::env::ArgsOs`], the config file is a path.\
+    /// Web args are from [`cliw::url_args::UrlArgs`], the config file is a
+    /// url that is fetched, needing the "web-config-fetch" feature.
+    ///
+    /// The config file is a flat JSON object or flat TOML, without tables.
+    /// A key names an arg by its long flag or id, the values are strings,
+    /// numbers, booleans or arrays of those.  A flag is set by `true`, or
+    /// by `false` for a `SetFalse` flag, and a counted flag is repeated by
+    /// a number, `verbose = 2`.  A count that isn't a number is an error.
+    ///
+    /// ``` toml
+    /// name = "Jo"
+    /// level = 3
+    /// tag = ["a", "b"]
+    /// ```
+    ///
+    /// Nothing is printed and the process never exits.
+    ///
+    /// # Errors
+    ///
+    /// [`WeError::Clap`] if clap fails to parse, help or version type
+    /// arguments are given, or the config file can't be read or parsed,
+    /// with kind [`ErrorKind::Io`](clap::error::ErrorKind::Io).  Call
+    /// `exit()` on it for the normal output.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::{WeError, WeParser}; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {
+    ///     #[arg(long)]
+    ///     config: Option<String>,
+    ///     #[arg(long)]
+    ///     name: Option<String>,
+    /// }
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// async fn load() -> Result<Opts, WeError> {
+    ///     Opts::we_parse_with_config("config").await
+    /// }
+    /// ```
+    fn we_parse_with_config<T>(config_arg: &str) -> impl Future<Output = Result<T, WeError>>
+    where
+        T: Parser,
+    {
+        let config_arg = config_arg.to_string();
+        async move {
+            let command = T::command();
+            let args = translate::args(&command)?
+                .into_iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect();
+            let args =
+                config_file::with_config(&command, args, &config_arg, config_file::read_config)
+                    .await?;
+            Ok(web_parse(args)?)
+        }
+    }
+
+    /// # Parse args after an async validation
+    ///
+    /// Gets the command line arguments on native or the web, hands them to
+    /// `validate`, and only parses them if it succeeds.  A security
+    /// checkpoint for sensitive widgets, for example checking a token from
+    /// the url against a remote allowlist.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// The order is translate, then validate, then parse.  The validator
+    /// sees the translated arguments exactly as clap will, `?token=abc`
+    /// arrives as `--token=abc`, and the first one is the binary name.
+    /// Clone what you need from the slice, the returned future can't borrow it.
+    ///
+    /// Nothing is printed and the process never exits.
+    ///
+    /// # Errors
+    ///
+    /// The validator's error if it fails, usually a [`WeError::Rejected`].
+    /// Otherwise [`WeError::Clap`] if clap fails to parse or help or version
+    /// type arguments are given.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::{WeError, WeParser}; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// async fn load() -> Result<Opts, WeError> {
+    ///     Opts::we_parse_async_validated(|args: &[String]| {
+    ///         let ok = !args.iter().any(|arg| arg.starts_with("--admin"));
+    ///         async move {
+    ///             // await your remote check here
+    ///             if ok {
+    ///                 Ok(())
+    ///             } else {
+    ///                 Err(WeError::Rejected("admin is not allowed".into()))
+    ///             }
+    ///         }
+    ///     })
+    ///     .await
+    /// }
+    /// ```
+    fn we_parse_async_validated<T, V, F>(validate: V) -> impl Future<Output = Result<T, WeError>>
+    where
+        T: Parser,
+        V: Fn(&[String]) -> F,
+        F: Future<Output = Result<(), WeError>>,
+    {
+        let args = translate::args(&T::command());
+        async move { parse_validated(args?, validate).await }
+    }
+
+    /// # Parse with required args relaxed on the web
+    ///
+    /// Like [`we_parse`](crate::WeParser::we_parse()) but on the web every
+    /// arg, including those of subcommands, is made optional with
+    /// [`clap::Command::mut_args`] before parsing.  Web links rarely supply
+    /// every arg, so a partial url still parses.\
+    /// Native args are from [`std::env::ArgsOs`] and are not relaxed, this
+    /// is the same as `we_parse`.\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// Only fields that can be empty benefit, like an
+    /// `#[arg(required = true)]` field of type `Option`.  A missing plain
+    /// `String` field still fails to parse, there is nothing to fill it with.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Exit
+    ///
+    /// This functon may call [`std::process::exit()`] after printing messages if
+    /// command line arguments are wrong or a help or version type argument is given.
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments to clap.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {
+    ///     /// Required on native, optional on the web
+    ///     #[arg(long, required = true)]
+    ///     pub name: Option<String>,
+    /// }
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// # if false {
+    /// // Type annotations needed
+    /// let opts: Opts = Opts::we_parse_relaxed();
+    /// # }
+    /// ```
+    #[must_use]
+    fn we_parse_relaxed<T>() -> T
+    where
+        T: Parser,
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Parser::try_parse().unwrap_or_else(|err| parser_exit::<Self>(&err))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let command = help::sized(relax(T::command()));
+            match translate::web_args(&command).and_then(|args| try_parse_with(command, args)) {
+                Ok(opts) => opts,
+                Err(err) => parser_exit::<Self>(&err),
+            }
+        }
+    }
+
+    /// # Parse without failing on bad values
+    ///
+    /// Best effort parsing for pages that should never fail on a malformed
+    /// link.  A value that doesn't validate, like `abc` in `?count=abc`, is
+    /// dropped and the args are parsed again, so the field gets its
+    /// default.  The errors of the dropped values are returned, to show
+    /// them or ignore them.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// Other errors, like an unknown arg or subcommand, or a missing
+    /// required arg, can't be fixed by dropping a value.  Then
+    /// `T::default()` is returned with every error so far, the last one
+    /// unrecoverable.  That is the [`Default`] of `T`, not the clap
+    /// defaults, a `default_value_t = 1` field is `0` unless `T`'s
+    /// `Default` says otherwise.  Help and version requests are returned the same
+    /// way, as their [`clap::Error`], and are not printed.  Nothing exits.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments to clap.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {
+    ///     #[arg(long, default_value_t = 1)]
+    ///     pub count: u32,
+    /// }
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// # if false {
+    /// // On the web `?count=abc` gives a count of 1 and one error.
+    /// let (opts, errors): (Opts, _) = Opts::we_parse_lenient();
+    /// for err in errors {
+    ///     eprintln!("{err}");
+    /// }
+    /// # }
+    /// ```
+    fn we_parse_lenient<T>() -> (T, Vec<error::Error>)
+    where
+        T: Parser + Default,
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let args = std::env::args_os()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect();
+            parse_lenient(&T::command(), args, |args: &[String]| {
+                T::try_parse_from(args)
+            })
         }
-
         #[cfg(target_arch = "wasm32")]
         {
-            let styled = &self.render_long_help();
-            cliw::output::print(&format!("{styled}"));
-            Ok(())
+            let command = T::command();
+            match translate::web_args(&command) {
+                Ok(args) => parse_lenient(&command, args, |args: &[String]| web_parse(args)),
+                Err(err) => (T::default(), vec![err]),
+            }
         }
     }
-}
 
-/// # Wrapper trait for [`clap::Parser`]
-///
-/// Functions to parse your Opt with clap on native or the web.\
-/// On native the arguments come from [`std::env::ArgsOs`].\
-/// On the web the arguments come from [`cliw::url_args::UrlArgs`].
-///
-/// Use these instead of their clap counterparts to make your command
-/// line program work on native or the web.
-///
-/// # Example
-/// ``` rust
-/// use clap::Parser; // Use clap to parse the arguments
-/// use we_clap::WeParser; // Use we_clap to provide the arguments.
-///
-/// #[derive(Parser, Debug, Default)]
-/// #[command(author, version, about, long_about)]
-/// pub struct Opts {}
-///
-/// // Implement web enabled parser for your struct
-/// impl we_clap::WeParser for Opts {}
-///
-/// // Use we_parse and it works on native or web.
-/// // Type annotations needed.
-/// let opts: Opts = Opts::we_parse();
-/// ```
-pub trait WeParser {
-    /// # Wrapper for [`clap::Parser::parse()`]
+    /// # Parse with positionals named in the url
     ///
-    /// Gets command line arguments on native or the web.\
-    /// Native args are from [`std::env::ArgsOs`].\
+    /// Like [`we_parse`](crate::WeParser::we_parse()) but on the web a
+    /// positional can be given by its name, `?src=a&dst=b` for the
+    /// positionals `src` and `dst`.  The values are put in the order the
+    /// command defines its positionals, whatever their order in the url.\
+    /// Native args are from [`std::env::ArgsOs`] and have real positionals,
+    /// this is the same as `we_parse`.\
     /// Web args are from [`cliw::url_args::UrlArgs`].
     ///
+    /// A key names a positional when it matches the positional's id and no
+    /// flag has the same name.  Name every positional before the last one
+    /// given, a gap shifts the later values into it.  Don't mix named
+    /// positionals with the [`Config::positional_key`].
+    ///
     /// # Panics
     ///
     /// May panic if contradictory arguments or settings exist (debug builds).
@@ -397,61 +2519,114 @@ pub trait WeParser {
     /// use we_clap::WeParser; // Use we_clap to provide the arguments to clap.
     ///
     /// #[derive(Parser, Debug, Default)]
-    /// #[command(author, version, about, long_about)]
-    /// pub struct Opts {}
+    /// pub struct Opts {
+    ///     src: Option<String>,
+    ///     dst: Option<String>,
+    /// }
     ///
-    /// // Implement web enabled parser for your struct
     /// impl we_clap::WeParser for Opts {}
     ///
-    /// // use we_parse and it works on native or web.
-    /// // Type annotations needed
-    /// let opts: Opts = Opts::we_parse();
+    /// // On the web `?src=a&dst=b` or `?dst=b&src=a`.
+    /// let opts: Opts = Opts::we_parse_named_positionals();
     /// ```
     #[must_use]
-    fn we_parse<T>() -> T
+    fn we_parse_named_positionals<T>() -> T
     where
         T: Parser,
     {
         #[cfg(not(target_arch = "wasm32"))]
         {
-            Parser::parse()
+            Parser::try_parse().unwrap_or_else(|err| parser_exit::<Self>(&err))
         }
         #[cfg(target_arch = "wasm32")]
         {
-            let opts = Parser::try_parse_from(UrlArgs::new());
-            match opts {
+            let command = T::command();
+            let args = translate::web_args(&command)
+                .map(|args| translate::name_positionals(&command, args));
+            match args.and_then(web_parse) {
                 Ok(opts) => opts,
-                Err(err) => {
-                    let msg = format!("{err}");
-                    match err.kind() {
-                        ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => {
-                            cliw::output::print(&msg);
-                        }
-                        _ => {
-                            cliw::output::eprint(&msg);
-                        }
-                    }
-
-                    std::process::exit(0); // Exit code meaningless on wasm.
-                }
+                Err(err) => parser_exit::<Self>(&err),
             }
         }
     }
-    /// # Wrapper for [`clap::Parser::try_parse()`]
+
+    /// # Parse as one type, falling back to another
     ///
-    /// Gets command line arguments on native or the web.\
+    /// Parses the command line arguments on native or the web as `T`, and
+    /// when that fails as `U`.  Use it to keep old links working, parse the
+    /// current `OptsV2` and fall back to the old `OptsV1`, then migrate.\
     /// Native args are from [`std::env::ArgsOs`].\
     /// Web args are from [`cliw::url_args::UrlArgs`].
     ///
+    /// `T` is tried first and wins when both would parse.  Help and version
+    /// requests are answered by `T`, the current type.  When both fail the
+    /// error from `U`, the last one tried, is reported.
+    ///
     /// # Panics
     ///
     /// May panic if contradictory arguments or settings exist (debug builds).
     /// This is normal clap behaviour.
     ///
-    /// # Errors
+    /// # Exit
     ///
-    /// If help or version type arguments are entered than [`clap::error`]
-    /// will be returned.  This is normal clap behaviour.
+    /// Exits on help, version or an error, like
+    /// [`we_parse`](crate::WeParser::we_parse()).
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::{Either, WeParser}; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct OptsV2 {}
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct OptsV1 {}
+    ///
+    /// impl we_clap::WeParser for OptsV2 {}
+    ///
+    /// let opts = match OptsV2::we_parse_fallback::<OptsV2, OptsV1>() {
+    ///     Either::Left(opts) => opts,
+    ///     Either::Right(_old) => OptsV2::default(), // migrate
+    /// };
+    /// ```
+    #[must_use]
+    fn we_parse_fallback<T, U>() -> Either<T, U>
+    where
+        T: Parser,
+        U: Parser,
+    {
+        let command = T::command();
+        let args = translate::args(&command).map_err(|err| err.into_clap(&command));
+        let fallback = || {
+            let command = U::command();
+            translate::args(&command).map_err(|err| err.into_clap(&command))
+        };
+        match args.and_then(|args| fallback::parse_fallback(args, fallback)) {
+            Ok(opts) => opts,
+            Err(err) => parser_exit::<Self>(&err),
+        }
+    }
+
+    /// # Parse, or show help and stop
+    ///
+    /// Parses the command line arguments on native or the web, without
+    /// exiting.  When parsing fails, or help or version is requested, the
+    /// message is shown in the element with id `element_id` and `None` is
+    /// returned.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// `None` means stop: the help is on screen and there are no args to
+    /// run with.  For an error the element shows the error message followed
+    /// by the help.  Needs the "web-help-element" feature to fill the
+    /// element.  Without it, on native, or when there is no such element,
+    /// the message goes to the regular output, stdout on native.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
     ///
     /// # Example
     /// ``` rust
@@ -459,43 +2634,484 @@ pub trait WeParser {
     /// use we_clap::WeParser; // Use we_clap to provide the arguments.
     ///
     /// #[derive(Parser, Debug, Default)]
-    /// #[command(author, version, about, long_about)]
     /// pub struct Opts {}
     ///
-    /// // Implement web enabled parser for your struct
     /// impl we_clap::WeParser for Opts {}
     ///
-    /// // use we_parse and it works on native or web.
-    /// // Type annotations needed
-    /// match Opts::we_try_parse::<Opts>() {
-    ///     Ok(opts) => {} // handle opts
-    ///     Err(err) => {} // handle error
+    /// let Some(opts) = Opts::we_parse_or_help::<Opts>("help") else {
+    ///     return; // Help is shown, stop here.
+    /// };
+    /// ```
+    fn we_parse_or_help<T>(element_id: &str) -> Option<T>
+    where
+        T: Parser,
+    {
+        let command = T::command();
+        let args = translate::args(&command).map_err(|err| err.into_clap(&command));
+        help::parse_or_help(args, |text| help::show_in(element_id, text))
+    }
+
+    /// # List the args that differ from their defaults
+    ///
+    /// Parses the command line arguments on native or the web, without
+    /// printing or exiting, and returns the `(id, value)` of every given arg
+    /// whose value differs from its default.  Drives a concise "your
+    /// settings" display or a minimal share link.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// Several values are joined with commas, like [`we_summary`].  An arg
+    /// given with its default value is left out.  Only the args of the top
+    /// command are listed, not its subcommand.  The list is empty when
+    /// parsing fails.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// for (id, value) in Opts::we_non_default_args::<Opts>() {
+    ///     println!("{id}: {value}");
     /// }
-    fn we_try_parse<T>() -> Result<T, error::Error>
+    /// ```
+    #[must_use]
+    fn we_non_default_args<T>() -> Vec<(String, String)>
     where
         T: Parser,
     {
-        #[cfg(target_arch = "wasm32")]
-        {
-            Parser::try_parse_from(UrlArgs::new())
+        let command = T::command();
+        let Ok(args) = translate::args(&command) else {
+            return Vec::new();
+        };
+        match command.clone().try_get_matches_from(args) {
+            Ok(matches) => matches::non_default(&command, &matches),
+            Err(_) => Vec::new(),
         }
+    }
+
+    /// # List required args that are missing
+    ///
+    /// Parses the command line arguments on native or the web, without
+    /// printing or exiting, and returns the required args that are missing.
+    /// Drives a "complete these fields" form on the web.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// The args are named as clap shows them in its error message, for
+    /// example `--name <NAME>`.  The list is empty when parsing would succeed,
+    /// or when it fails for any other reason.
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug, Default)]
+    /// pub struct Opts {}
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// for missing in Opts::we_missing_required::<Opts>() {
+    ///     println!("Please fill in {missing}");
+    /// }
+    /// ```
+    #[must_use]
+    fn we_missing_required<T>() -> Vec<String>
+    where
+        T: Parser,
+    {
+        let command = T::command();
+        let Ok(args) = translate::args(&command) else {
+            return Vec::new();
+        };
+        missing_required(&command.try_get_matches_from(args))
+    }
+
+    /// # Parse, prompting for missing required args on the web
+    ///
+    /// Like [`we_parse`](crate::WeParser::we_parse()), but instead of
+    /// failing for missing required args on the web, asks for each with
+    /// `window.prompt("value for --name")`, adds the answers to the args
+    /// and parses again.  For a page opened without a query string.  A
+    /// cancelled prompt gives the usual error and exit.\
+    /// Native args are from [`std::env::ArgsOs`], there is no prompt and
+    /// this is the same as `we_parse`.\
+    /// Web args are from [`cliw::url_args::UrlArgs`].
+    ///
+    /// # Panics
+    ///
+    /// May panic if contradictory arguments or settings exist (debug builds).
+    /// This is normal clap behaviour.
+    ///
+    /// # Exit
+    ///
+    /// This functon may call [`std::process::exit()`] after printing messages if
+    /// command line arguments are wrong or a help or version type argument is given.
+    /// This is normal clap behaviour.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use clap::Parser; // Use clap to parse the arguments
+    /// use we_clap::WeParser; // Use we_clap to provide the arguments.
+    ///
+    /// #[derive(Parser, Debug)]
+    /// pub struct Opts {
+    ///     #[arg(long)]
+    ///     name: String,
+    /// }
+    ///
+    /// impl we_clap::WeParser for Opts {}
+    ///
+    /// # if false {
+    /// // Type annotations needed
+    /// let opts: Opts = Opts::we_parse_interactive();
+    /// # }
+    /// ```
+    #[must_use]
+    fn we_parse_interactive<T>() -> T
+    where
+        T: Parser,
+    {
         #[cfg(not(target_arch = "wasm32"))]
         {
-            Parser::try_parse()
+            Parser::try_parse().unwrap_or_else(|err| parser_exit::<Self>(&err))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let command = T::command();
+            let mut args =
+                translate::web_args(&command).unwrap_or_else(|err| parser_exit::<Self>(&err));
+            let prompt = |message: &str| {
+                web_sys::window()?
+                    .prompt_with_message(message)
+                    .ok()
+                    .flatten()
+            };
+            match parse_interactive(&mut args, prompt) {
+                Ok(opts) => opts,
+                Err(err) => parser_exit::<Self>(&web_error(&command, &args, err)),
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    //   use super::*;
-    //   lazy: let us only use what we need.
-    //   Or maybe we do need to use everything because we test everything ?
-    /*
-        #[test]
-        fn it_works() {
-            let result = add(2, 2);
-            assert_eq!(result, 4);
-        }
-    */
+    use super::*;
+    use clap::Arg;
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Long option names must be unique")]
+    fn debug_assert_misconfigured() {
+        Command::new("app")
+            .arg(Arg::new("one").long("name"))
+            .arg(Arg::new("two").long("name"))
+            .we_debug_assert();
+    }
+
+    #[test]
+    fn usage_names_program() {
+        let command = Command::new("app").arg(Arg::new("file").required(true));
+        let usage = |command: &Command| {
+            let args = source_args(command, &vec!["https://example.org/app.html"]).unwrap();
+            let err = command.clone().try_get_matches_from(args).unwrap_err();
+            err.render().to_string()
+        };
+        assert!(usage(&command).contains("Usage: app <file>"));
+        set_program_name(ProgramName::DocumentTitle);
+        assert!(usage(&command).contains("Usage: app <file>"));
+        set_program_name("tool");
+        assert!(usage(&command).contains("Usage: tool <file>"));
+    }
+
+    #[test]
+    fn missing_two_required() {
+        let command = Command::new("form")
+            .arg(Arg::new("first").long("first").required(true))
+            .arg(Arg::new("second").long("second").required(true))
+            .arg(Arg::new("third").long("third").required(true));
+
+        let result = command.clone().try_get_matches_from(["form", "--second=2"]);
+        assert_eq!(
+            missing_required(&result),
+            ["--first <first>", "--third <third>"]
+        );
+
+        let result = command.try_get_matches_from(["form", "--first=1", "--second=2", "--third=3"]);
+        assert!(missing_required(&result).is_empty());
+    }
+
+    #[test]
+    fn prompts_for_missing() {
+        #[derive(Parser, Debug, PartialEq)]
+        struct Opts {
+            #[arg(long)]
+            name: String,
+            file: String,
+        }
+
+        let mut asked = Vec::new();
+        let mut args = vec!["app".to_string()];
+        let opts = parse_interactive::<Opts>(&mut args, |message| {
+            asked.push(message.to_string());
+            Some(
+                if message.contains("name") {
+                    "Jo"
+                } else {
+                    "in.txt"
+                }
+                .to_string(),
+            )
+        });
+        assert_eq!(
+            opts.unwrap(),
+            Opts {
+                name: "Jo".into(),
+                file: "in.txt".into()
+            }
+        );
+        assert_eq!(asked, ["value for --name", "value for <FILE>"]);
+        assert_eq!(args, ["app", "--name=Jo", "in.txt"]);
+
+        let mut args = vec!["app".to_string(), "in.txt".to_string()];
+        let cancelled = parse_interactive::<Opts>(&mut args, |_| None);
+        assert_eq!(
+            cancelled.unwrap_err().kind(),
+            error::ErrorKind::MissingRequiredArgument
+        );
+        assert_eq!(args, ["app", "in.txt"]);
+    }
+
+    #[test]
+    fn async_abort() {
+        use std::future::{pending, ready};
+
+        #[derive(Parser, Debug)]
+        struct Opts {
+            #[arg(long)]
+            count: u8,
+        }
+        impl WeParser for Opts {}
+
+        let parse = Opts::we_parse_async_abortable::<Opts, Vec<&str>, _>(pending(), ready(()));
+        assert!(matches!(future::block_on(parse), Err(WeError::Aborted)));
+
+        let parse = Opts::we_parse_async_abortable(ready(vec!["app", "--count=2"]), pending());
+        let opts: Opts = future::block_on(parse).unwrap();
+        assert_eq!(opts.count, 2);
+    }
+
+    #[test]
+    fn async_validated() {
+        #[derive(Parser, Debug)]
+        struct Opts {
+            #[arg(long)]
+            token: String,
+        }
+
+        let allow = |args: &[String]| {
+            let ok = args.contains(&"--token=good".to_string());
+            async move {
+                if ok {
+                    Ok(())
+                } else {
+                    Err(WeError::Rejected("bad token".into()))
+                }
+            }
+        };
+
+        let args = vec!["app".into(), "--token=good".into()];
+        let opts: Opts = future::block_on(parse_validated(args, allow)).unwrap();
+        assert_eq!(opts.token, "good");
+
+        let args = vec!["app".into(), "--token=evil".into()];
+        let err = future::block_on(parse_validated::<Opts, _, _>(args, allow)).unwrap_err();
+        assert!(matches!(err, WeError::Rejected(reason) if reason == "bad token"));
+    }
+
+    #[test]
+    fn relaxed_required() {
+        use clap::CommandFactory;
+
+        #[derive(Parser, Debug)]
+        struct Opts {
+            #[arg(long, required = true)]
+            name: Option<String>,
+            #[command(subcommand)]
+            run: Option<Run>,
+        }
+        #[derive(clap::Subcommand, Debug)]
+        enum Run {
+            Fast {
+                #[arg(long, required = true)]
+                speed: Option<u8>,
+            },
+        }
+
+        assert!(Opts::try_parse_from(["app"]).is_err());
+
+        let opts: Opts = try_parse_with(relax(Opts::command()), ["app", "fast"]).unwrap();
+        assert_eq!(opts.name, None);
+        assert!(matches!(opts.run, Some(Run::Fast { speed: None })));
+
+        let opts: Opts = try_parse_with(relax(Opts::command()), ["app", "--name=x"]).unwrap();
+        assert_eq!(opts.name.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn lenient_values() {
+        use clap::CommandFactory;
+        use error::ErrorKind;
+
+        #[derive(Parser, Debug, Default, PartialEq)]
+        struct Opts {
+            #[arg(long, default_value_t = 1)]
+            count: u32,
+            #[arg(long, value_parser = ["a", "b"])]
+            mode: Option<String>,
+            #[arg(long)]
+            name: Option<String>,
+            size: Option<u8>,
+            #[command(subcommand)]
+            run: Option<Run>,
+        }
+        #[derive(clap::Subcommand, Debug, PartialEq)]
+        enum Run {
+            Fast,
+        }
+
+        let parse = |args: &[&str]| {
+            let args = args.iter().map(ToString::to_string).collect();
+            parse_lenient(&Opts::command(), args, |args: &[String]| {
+                Opts::try_parse_from(args)
+            })
+        };
+        let (opts, errors) = parse(&["app", "--count=abc", "--mode", "c", "--name=bob", "999"]);
+        assert_eq!(opts.count, 1);
+        assert_eq!((opts.mode, opts.size), (None, None));
+        assert_eq!(opts.name.as_deref(), Some("bob"));
+        let kinds: Vec<ErrorKind> = errors.iter().map(error::Error::kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ErrorKind::ValueValidation,
+                ErrorKind::InvalidValue,
+                ErrorKind::ValueValidation
+            ]
+        );
+
+        let (opts, errors) = parse(&["app", "--count=2", "--name=bob", "--bogus"]);
+        assert_eq!(opts, Opts::default());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), ErrorKind::UnknownArgument);
+
+        let (opts, errors) = parse(&["app", "--count=2", "fast"]);
+        assert_eq!((opts.count, opts.run), (2, Some(Run::Fast)));
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn lenient_same_values() {
+        use clap::CommandFactory;
+
+        #[derive(Parser, Debug, Default, PartialEq)]
+        struct Opts {
+            #[arg(long)]
+            name: Option<String>,
+            #[arg(short, long)]
+            count: Option<u32>,
+            size: Option<u8>,
+        }
+
+        let parse = |args: &[&str]| {
+            let args = args.iter().map(ToString::to_string).collect();
+            parse_lenient(&Opts::command(), args, |args: &[String]| {
+                Opts::try_parse_from(args)
+            })
+        };
+        let (opts, errors) = parse(&["app", "--name=abc", "--count=abc"]);
+        assert_eq!(opts.name.as_deref(), Some("abc"));
+        assert_eq!(opts.count, None);
+        assert_eq!(errors.len(), 1);
+
+        let (opts, errors) = parse(&["app", "--name", "300", "-c", "300", "300"]);
+        assert_eq!(opts.name.as_deref(), Some("300"));
+        assert_eq!((opts.count, opts.size), (Some(300), None));
+        assert_eq!(errors.len(), 1);
+
+        let (opts, errors) = parse(&["app", "--name", "x", "x", "-cx"]);
+        assert_eq!(opts.name.as_deref(), Some("x"));
+        assert_eq!((opts.count, opts.size), (None, None));
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn start_callback() {
+        #[derive(Parser, Debug)]
+        #[command(version = "1.0")]
+        struct Opts {
+            #[arg(long)]
+            count: u8,
+        }
+
+        let mut count = None;
+        start(Opts::try_parse_from(["app", "--count=3"]), |opts: Opts| {
+            count = Some(opts.count);
+        });
+        assert_eq!(count, Some(3));
+
+        // Help, version and errors are reported, the test keeps running.
+        for args in [["app", "--help"], ["app", "--version"], ["app", "--bad"]] {
+            let mut called = false;
+            start(Opts::try_parse_from(args), |_: Opts| called = true);
+            assert!(!called);
+        }
+    }
+
+    #[test]
+    fn parser_exit_code() {
+        const CHILD: &str = "WE_CLAP_PARSER_EXIT_CHILD";
+
+        #[derive(Parser, Debug)]
+        struct Opts {
+            #[arg(long)]
+            _name: String,
+        }
+        impl WeParser for Opts {
+            fn we_exit_code(kind: error::ErrorKind) -> i32 {
+                match kind {
+                    error::ErrorKind::MissingRequiredArgument => 3,
+                    kind => we_exit_code(kind),
+                }
+            }
+        }
+
+        if std::env::var_os(CHILD).is_some() {
+            let _: Opts = Opts::we_parse_from(["app"]);
+        }
+
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "tests::parser_exit_code", "--nocapture"])
+            .env(CHILD, "1")
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(we_exit_code(error::ErrorKind::DisplayVersion), 0);
+    }
 }
@@ -0,0 +1,549 @@
+//! # Helpers for parsed [`ArgMatches`]
+
+use crate::config::Config;
+use crate::source::ArgSource;
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgMatches, Command, CommandFactory};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+/// Characters encoded in query keys and values.
+const QUERY: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'+')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`');
+
+/// # Matches that know where their args came from
+///
+/// From [`WeCommand::we_get_matches_we`](crate::WeCommand::we_get_matches_we).
+/// Derefs to the [`ArgMatches`], so `get_one` and `get_many` work as usual,
+/// and keeps the [`ArgSource`] the args were read from, to log where the
+/// values came from when web and native behave differently.
+#[derive(Clone, Debug)]
+pub struct WeMatches {
+    matches: ArgMatches,
+    source: Option<ArgSource>,
+}
+
+impl WeMatches {
+    /// The matches, with the arg source of this thread on the web.
+    pub(crate) fn new(matches: ArgMatches) -> Self {
+        let source = cfg!(target_arch = "wasm32").then(crate::source::arg_source);
+        Self { matches, source }
+    }
+
+    /// # Where the args were read from
+    ///
+    /// The [`ArgSource`] set when the args were parsed on the web, or
+    /// `None` on native where they are the command line.
+    #[must_use]
+    pub fn source(&self) -> Option<&ArgSource> {
+        self.source.as_ref()
+    }
+
+    /// The plain [`ArgMatches`].
+    #[must_use]
+    pub fn into_inner(self) -> ArgMatches {
+        self.matches
+    }
+}
+
+impl std::ops::Deref for WeMatches {
+    type Target = ArgMatches;
+
+    fn deref(&self) -> &ArgMatches {
+        &self.matches
+    }
+}
+
+/// # One line summary of the given args
+///
+/// A terse `key=value key2=value2` line of every arg given on the command
+/// line, or the url on the web, for a status bar or footer.  Args left at
+/// their defaults are not shown.  Several values are joined with commas,
+/// `tag=a,b`.  Flags show their value, `verbose=true`.
+///
+/// Only the args of the matched command are shown, not its subcommand.
+///
+/// # Example
+/// ``` rust
+/// use clap::{Arg, ArgAction, Command};
+///
+/// let matches = Command::new("app")
+///     .arg(Arg::new("verbose").long("verbose").action(ArgAction::SetTrue))
+///     .arg(Arg::new("name").long("name"))
+///     .get_matches_from(["app", "--verbose", "--name=bob"]);
+///
+/// assert_eq!(we_clap::we_summary(&matches), "verbose=true name=bob");
+/// ```
+#[must_use]
+pub fn we_summary(matches: &ArgMatches) -> String {
+    given(matches, true)
+        .map(|(id, values)| format!("{id}={}", values.join(",")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// # Turn parsed args back into a url query string
+///
+/// The reverse of parsing on the web.  Every arg given on the command line,
+/// or the url, is written as a query string that parses back to the same
+/// matches.  Args left at their defaults, or given their default values,
+/// are skipped to keep links short, and so are args from env, which
+/// belong to the machine, not the link.  Subcommands are included.
+///
+/// * Options become `key=value`, repeated for several values.
+/// * Flags become `--flag`, or `flag=true` with
+///   [`Config::flag_values`](crate::Config::flag_values).  Counts repeat the
+///   flag.
+/// * Positionals use the [`Config::positional_key`](crate::Config::positional_key).
+///
+/// The `command` must be the one that produced the `matches`.  With a
+/// derive struct use `Opts::command()` and keep the `ArgMatches` from
+/// parsing around, the struct itself can't be turned back into args.
+///
+/// # Example
+/// ``` rust
+/// use clap::{Arg, ArgAction, Command};
+///
+/// let command = Command::new("app")
+///     .arg(Arg::new("verbose").long("verbose").action(ArgAction::SetTrue))
+///     .arg(Arg::new("name").long("name"));
+/// let matches = command.clone().get_matches_from(["app", "--verbose", "--name=Jo Jo"]);
+///
+/// assert_eq!(we_clap::we_to_query(&command, &matches), "?--verbose&name=Jo%20Jo");
+/// ```
+#[must_use]
+pub fn we_to_query(command: &Command, matches: &ArgMatches) -> String {
+    let mut queries = Vec::new();
+    push_queries(command, matches, &crate::config(), &mut queries);
+    if queries.is_empty() {
+        String::new()
+    } else {
+        format!("?{}", queries.join("&"))
+    }
+}
+
+/// # Turn parsed args back into a shareable url
+///
+/// The `base` url, up to any query string or hash, with the query from
+/// [`we_to_query`] added.  Use it for a "copy link" button.
+///
+/// # Example
+/// ``` rust
+/// use clap::{Arg, Command};
+///
+/// let command = Command::new("app").arg(Arg::new("level").long("level"));
+/// let matches = command.clone().get_matches_from(["app", "--level=3"]);
+///
+/// let url = we_clap::we_to_url("https://example.org/app.html?old#top", &command, &matches);
+/// assert_eq!(url, "https://example.org/app.html?level=3");
+/// ```
+#[must_use]
+pub fn we_to_url(base: &str, command: &Command, matches: &ArgMatches) -> String {
+    let end = base.find(['?', '#']).unwrap_or(base.len());
+    format!("{}{}", &base[..end], we_to_query(command, matches))
+}
+
+/// # Turn a parsed struct back into a shareable link
+///
+/// For links made from the app state, after the parsed struct was changed
+/// by the app, when the [`ArgMatches`] for [`we_to_query`] are long gone.
+/// Clap can't turn a struct back into args, so implement
+/// [`we_to_args`](WeToQuery::we_to_args()) to write the struct as a
+/// command line.  The provided methods parse those args with the command of
+/// the struct and write the query like [`we_to_query`], so values equal to
+/// their defaults are skipped and `true` flags are bare `--flag`s.
+///
+/// # Example
+/// ``` rust
+/// use clap::Parser;
+/// use we_clap::WeToQuery;
+///
+/// #[derive(Parser)]
+/// struct Opts {
+///     #[arg(long)]
+///     verbose: bool,
+///     #[arg(long, default_value_t = 1)]
+///     page: u32,
+/// }
+///
+/// impl WeToQuery for Opts {
+///     fn we_to_args(&self) -> Vec<String> {
+///         let mut args = vec![format!("--page={}", self.page)];
+///         if self.verbose {
+///             args.push("--verbose".to_string());
+///         }
+///         args
+///     }
+/// }
+///
+/// let opts = Opts { verbose: true, page: 1 };
+/// assert_eq!(opts.we_to_query(), "?--verbose");
+///
+/// let opts = Opts { verbose: false, page: 4 };
+/// assert_eq!(opts.we_to_url("https://example.org/app.html?page=3"), "https://example.org/app.html?page=4");
+/// ```
+pub trait WeToQuery: CommandFactory {
+    /// The struct as command line args, without the program name.
+    fn we_to_args(&self) -> Vec<String>;
+
+    /// # The struct as a url query string
+    ///
+    /// See [`we_to_query`].
+    ///
+    /// # Panics
+    ///
+    /// If the args from [`we_to_args`](WeToQuery::we_to_args()) don't
+    /// parse, a bug in the impl.
+    #[must_use]
+    fn we_to_query(&self) -> String {
+        let command = Self::command();
+        let args = std::iter::once(command.get_name().to_string()).chain(self.we_to_args());
+        let matches = command
+            .clone()
+            .try_get_matches_from(args)
+            .unwrap_or_else(|err| panic!("args from `we_to_args` don't parse: {err}"));
+        we_to_query(&command, &matches)
+    }
+
+    /// # The struct as a shareable url
+    ///
+    /// The `base` url, up to any query string or hash, with the query from
+    /// [`we_to_query`](WeToQuery::we_to_query()) added.
+    ///
+    /// # Panics
+    ///
+    /// If the args from [`we_to_args`](WeToQuery::we_to_args()) don't
+    /// parse, a bug in the impl.
+    #[must_use]
+    fn we_to_url(&self, base: &str) -> String {
+        let end = base.find(['?', '#']).unwrap_or(base.len());
+        format!("{}{}", &base[..end], self.we_to_query())
+    }
+}
+
+/// Ids and values of the given args that differ from their defaults.
+///
+/// Several values are joined with commas, like [`we_summary`].
+pub(crate) fn non_default(command: &Command, matches: &ArgMatches) -> Vec<(String, String)> {
+    given(matches, true)
+        .filter(|(id, values)| !is_default(command, id, values))
+        .map(|(id, values)| (id.to_string(), values.join(",")))
+        .collect()
+}
+
+/// Whether the values of an arg are just its default values.
+fn is_default(command: &Command, id: &str, values: &[String]) -> bool {
+    command
+        .get_arguments()
+        .find(|arg| arg.get_id() == id)
+        .is_some_and(|arg| {
+            let defaults = arg.get_default_values();
+            !defaults.is_empty()
+                && defaults
+                    .iter()
+                    .map(|value| value.to_string_lossy())
+                    .eq(values.iter().map(String::as_str))
+        })
+}
+
+/// Add the queries for the given args of a command and its subcommand.
+fn push_queries(
+    command: &Command,
+    matches: &ArgMatches,
+    config: &Config,
+    queries: &mut Vec<String>,
+) {
+    for (id, values) in given(matches, false) {
+        if is_default(command, id, &values) {
+            continue;
+        }
+        let Some(arg) = command.get_arguments().find(|arg| arg.get_id() == id) else {
+            continue;
+        };
+        push_arg(arg, &values, config, queries);
+    }
+    if let Some((name, sub_matches)) = matches.subcommand() {
+        if let Some(subcommand) = command.find_subcommand(name) {
+            queries.push(encode(name));
+            push_queries(subcommand, sub_matches, config, queries);
+        }
+    }
+}
+
+/// Add the queries for one arg.
+fn push_arg(arg: &Arg, values: &[String], config: &Config, queries: &mut Vec<String>) {
+    let key = arg
+        .get_long()
+        .map(String::from)
+        .or_else(|| arg.get_short().map(String::from));
+    match (arg.get_action(), key) {
+        (ArgAction::SetTrue | ArgAction::SetFalse, Some(key)) => {
+            if config.flag_values {
+                queries.extend(
+                    values
+                        .first()
+                        .map(|value| format!("{}={}", encode(&key), encode(value))),
+                );
+            } else {
+                queries.push(flag(arg));
+            }
+        }
+        (ArgAction::Count, Some(_)) => {
+            let count = values
+                .first()
+                .and_then(|count| count.parse().ok())
+                .unwrap_or(0);
+            queries.extend(std::iter::repeat_n(flag(arg), count));
+        }
+        (_, Some(key)) => {
+            queries.extend(
+                values
+                    .iter()
+                    .map(|value| format!("{}={}", encode(&key), encode(value))),
+            );
+        }
+        (_, None) => {
+            queries.extend(
+                values
+                    .iter()
+                    .map(|value| format!("{}={}", config.positional_key, encode(value))),
+            );
+        }
+    }
+}
+
+/// The command line flag for an arg, long if it has one.
+fn flag(arg: &Arg) -> String {
+    match (arg.get_long(), arg.get_short()) {
+        (Some(long), _) => format!("--{}", encode(long)),
+        (None, Some(short)) => format!("-{}", encode(&short.to_string())),
+        (None, None) => String::new(),
+    }
+}
+
+/// Percent encode a query key or value.
+fn encode(text: &str) -> String {
+    utf8_percent_encode(text, QUERY).to_string()
+}
+
+/// Ids and raw values of the args given on the command line, and with
+/// `env` from env too.
+fn given(matches: &ArgMatches, env: bool) -> impl Iterator<Item = (&str, Vec<String>)> {
+    matches.ids().filter_map(move |id| {
+        let id = id.as_str();
+        match matches.value_source(id) {
+            Some(ValueSource::CommandLine) => {}
+            Some(ValueSource::EnvVariable) if env => {}
+            _ => return None,
+        }
+        let values = matches.try_get_raw(id).ok()??;
+        Some((
+            id,
+            values
+                .map(|value| value.to_string_lossy().into_owned())
+                .collect(),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction, Command};
+
+    #[test]
+    fn matches_with_source() {
+        let matches = Command::new("app")
+            .arg(Arg::new("name").long("name"))
+            .try_get_matches_from(["app", "--name=bob"])
+            .unwrap();
+        let matches = WeMatches::new(matches);
+        assert_eq!(matches.get_one::<String>("name").unwrap(), "bob");
+        assert_eq!(matches.source(), None);
+        assert!(matches.into_inner().contains_id("name"));
+    }
+
+    #[test]
+    fn summary() {
+        let matches = Command::new("app")
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(Arg::new("tag").long("tag").action(ArgAction::Append))
+            .arg(Arg::new("level").long("level").default_value("1"))
+            .try_get_matches_from(["app", "--tag=a", "--verbose", "--tag", "b"])
+            .unwrap();
+        assert_eq!(we_summary(&matches), "tag=a,b verbose=true");
+    }
+
+    #[test]
+    fn round_trip_url() {
+        use crate::translate::tests::url_args;
+        use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+
+        #[derive(Parser, Debug, PartialEq)]
+        struct Opts {
+            #[arg(short, long)]
+            verbose: bool,
+            #[arg(short = 'l', action = ArgAction::Count)]
+            level: u8,
+            #[arg(long)]
+            tag: Vec<String>,
+            #[arg(long, default_value = "plain")]
+            style: String,
+            file: Option<String>,
+            #[command(subcommand)]
+            run: Option<Run>,
+        }
+        #[derive(Subcommand, Debug, PartialEq)]
+        enum Run {
+            Fast {
+                #[arg(long)]
+                speed: i32,
+            },
+        }
+
+        let command = Opts::command();
+        let matches = command
+            .clone()
+            .try_get_matches_from(url_args(
+                "https://example.org/?-v&-l&-l&tag=a%26b&tag=c d&_=in.txt&fast&speed=-2",
+            ))
+            .unwrap();
+        let url = we_to_url("https://example.org/", &command, &matches);
+        assert_eq!(
+            url,
+            "https://example.org/?--verbose&-l&-l&tag=a%26b&tag=c%20d&_=in.txt&fast&speed=-2"
+        );
+
+        let again = command.try_get_matches_from(url_args(&url)).unwrap();
+        assert_eq!(
+            Opts::from_arg_matches(&matches).unwrap(),
+            Opts::from_arg_matches(&again).unwrap()
+        );
+    }
+
+    #[test]
+    fn flag_styles() {
+        let command = Command::new("app").arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .action(ArgAction::SetTrue),
+        );
+        let matches = command
+            .clone()
+            .try_get_matches_from(["app", "--verbose"])
+            .unwrap();
+        let query = |config: &Config| {
+            let mut queries = Vec::new();
+            push_queries(&command, &matches, config, &mut queries);
+            queries
+        };
+
+        assert_eq!(query(&Config::default()), ["--verbose"]);
+        let config = Config {
+            flag_values: true,
+            ..Config::default()
+        };
+        assert_eq!(query(&config), ["verbose=true"]);
+    }
+
+    #[cfg(feature = "web-env")]
+    #[test]
+    fn env_not_shared() {
+        // clap reads the variable when the arg is built.
+        std::env::set_var("WE_CLAP_TEST_SHARE_DEBUG", "true");
+        let command = Command::new("app")
+            .arg(
+                Arg::new("debug")
+                    .long("debug")
+                    .env("WE_CLAP_TEST_SHARE_DEBUG")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(Arg::new("name").long("name"));
+        std::env::remove_var("WE_CLAP_TEST_SHARE_DEBUG");
+        let matches = command
+            .clone()
+            .try_get_matches_from(["app", "--name=Jo"])
+            .unwrap();
+        assert_eq!(we_to_query(&command, &matches), "?name=Jo");
+        assert_eq!(we_summary(&matches), "name=Jo debug=true");
+    }
+
+    #[test]
+    fn struct_to_query() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        #[command(name = "app")]
+        struct Opts {
+            #[arg(short, long)]
+            verbose: bool,
+            #[arg(long, default_value = "plain")]
+            style: String,
+            #[arg(long)]
+            tag: Vec<String>,
+        }
+        impl WeToQuery for Opts {
+            fn we_to_args(&self) -> Vec<String> {
+                let mut args = vec![format!("--style={}", self.style)];
+                args.extend(self.tag.iter().map(|tag| format!("--tag={tag}")));
+                if self.verbose {
+                    args.push("-v".to_string());
+                }
+                args
+            }
+        }
+
+        let opts = Opts {
+            verbose: true,
+            style: "plain".to_string(),
+            tag: vec!["a b".to_string(), "c".to_string()],
+        };
+        assert_eq!(opts.we_to_query(), "?tag=a%20b&tag=c&--verbose");
+
+        let opts = Opts {
+            verbose: false,
+            style: "fancy".to_string(),
+            tag: Vec::new(),
+        };
+        assert_eq!(
+            opts.we_to_url("https://example.org/#top"),
+            "https://example.org/?style=fancy"
+        );
+    }
+
+    #[test]
+    fn non_default_args() {
+        let command = Command::new("app")
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(Arg::new("level").long("level").default_value("1"))
+            .arg(Arg::new("style").long("style").default_value("plain"))
+            .arg(Arg::new("name").long("name"));
+        let matches = command
+            .clone()
+            .try_get_matches_from(["app", "--level=3", "--style=plain", "--name=bob"])
+            .unwrap();
+
+        assert_eq!(
+            non_default(&command, &matches),
+            [
+                ("level".to_string(), "3".to_string()),
+                ("name".to_string(), "bob".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! # Subcommand listing for web menus
+
+use clap::Command;
+
+/// # A subcommand, for a generated menu
+///
+/// Listed by [`WeCommand::we_subcommands()`](crate::WeCommand::we_subcommands()).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubcommandInfo {
+    /// The subcommand name, as typed on the command line.
+    pub name: String,
+    /// The short about text, if any.
+    pub about: Option<String>,
+    /// Whether the subcommand has subcommands of its own, for a submenu.
+    pub has_subcommands: bool,
+}
+
+/// The visible subcommands of a command.
+pub(crate) fn subcommands(command: &Command) -> Vec<SubcommandInfo> {
+    command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set())
+        .map(|subcommand| SubcommandInfo {
+            name: subcommand.get_name().to_string(),
+            about: subcommand.get_about().map(ToString::to_string),
+            has_subcommands: subcommand.has_subcommands(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu() {
+        let command = Command::new("tools")
+            .subcommand(Command::new("convert").about("Convert a file"))
+            .subcommand(
+                Command::new("remote")
+                    .about("Remote tools")
+                    .subcommand(Command::new("push")),
+            )
+            .subcommand(Command::new("debug").hide(true))
+            .subcommand(Command::new("plain"));
+
+        assert_eq!(
+            subcommands(&command),
+            [
+                SubcommandInfo {
+                    name: "convert".into(),
+                    about: Some("Convert a file".into()),
+                    has_subcommands: false,
+                },
+                SubcommandInfo {
+                    name: "remote".into(),
+                    about: Some("Remote tools".into()),
+                    has_subcommands: true,
+                },
+                SubcommandInfo {
+                    name: "plain".into(),
+                    about: None,
+                    has_subcommands: false,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! # Args from a parent frame
+//!
+//! A wasm module embedded in an `<iframe>` may get its config from the host
+//! page with `postMessage` instead of the url.  With
+//! [`ArgSource::PostMessage`](crate::ArgSource::PostMessage) and the
+//! "web-post-message" feature
+//! [`we_parse_async`](crate::WeParser::we_parse_async()) waits for the first
+//! message of args from the parent, or falls back to the query string after
+//! a timeout.
+
+use crate::source::from_url;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+/// The `data` of a message event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum MessageData {
+    /// A string, a query string like `verbose&name=Jo`.
+    Text(String),
+    /// An array of strings, one query string each.
+    List(Vec<String>),
+    /// Anything else, not args.
+    Other,
+}
+
+/// The url args of a message, the page url first.  `None` if the message
+/// isn't args.
+pub(crate) fn message_args(page: &str, data: MessageData) -> Option<Vec<String>> {
+    let queries: Vec<String> = match data {
+        MessageData::Text(text) => from_url(&format!("?{text}"))
+            .skip(1)
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect(),
+        MessageData::List(list) => list,
+        MessageData::Other => return None,
+    };
+    Some(std::iter::once(page.to_string()).chain(queries).collect())
+}
+
+/// Where the message listener and the timer leave their result for the
+/// future.
+#[derive(Default)]
+struct Settled {
+    /// `Some(None)` once timed out.
+    result: Option<Option<Vec<String>>>,
+    waker: Option<Waker>,
+}
+
+impl Settled {
+    /// Keep the first result only, and wake the future.
+    fn settle(&mut self, result: Option<Vec<String>>) {
+        if self.result.is_none() {
+            self.result = Some(result);
+            if let Some(waker) = self.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Resolves to the args of the first message, or `None` on timeout.
+struct MessageArgs {
+    settled: Rc<RefCell<Settled>>,
+    #[cfg(target_arch = "wasm32")]
+    listener: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::MessageEvent)>,
+    #[cfg(target_arch = "wasm32")]
+    timer: Option<(i32, wasm_bindgen::closure::Closure<dyn FnMut()>)>,
+}
+
+impl Future for MessageArgs {
+    type Output = Option<Vec<String>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut settled = self.settled.borrow_mut();
+        match settled.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                settled.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Listen for the first message of args from the parent frame, for up to
+/// `timeout_ms`.
+#[cfg(target_arch = "wasm32")]
+fn listen(window: &web_sys::Window, timeout_ms: u32) -> MessageArgs {
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let settled: Rc<RefCell<Settled>> = Rc::default();
+    let page = window.location().href().unwrap_or_default();
+    let parent = window.parent().ok().flatten();
+    let from_message = Rc::clone(&settled);
+    let listener =
+        Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+            let from_parent = match (event.source(), &parent) {
+                (Some(source), Some(parent)) => js_sys::Object::is(&source, parent),
+                _ => false,
+            };
+            if from_parent {
+                if let Some(args) = message_args(&page, data(&event.data())) {
+                    from_message.borrow_mut().settle(Some(args));
+                }
+            }
+        });
+    let _ = window.add_event_listener_with_callback("message", listener.as_ref().unchecked_ref());
+    let from_timer = Rc::clone(&settled);
+    let timeout = Closure::<dyn FnMut()>::new(move || from_timer.borrow_mut().settle(None));
+    let timer = window
+        .set_timeout_with_callback_and_timeout_and_arguments_0(
+            timeout.as_ref().unchecked_ref(),
+            i32::try_from(timeout_ms).unwrap_or(i32::MAX),
+        )
+        .ok()
+        .map(|handle| (handle, timeout));
+    MessageArgs {
+        settled,
+        listener,
+        timer,
+    }
+}
+
+/// The message data as args, a string or an array of strings.
+#[cfg(target_arch = "wasm32")]
+fn data(value: &wasm_bindgen::JsValue) -> MessageData {
+    if let Some(text) = value.as_string() {
+        return MessageData::Text(text);
+    }
+    if js_sys::Array::is_array(value) {
+        let list: Option<Vec<String>> = js_sys::Array::from(value)
+            .iter()
+            .map(|item| item.as_string())
+            .collect();
+        return list.map_or(MessageData::Other, MessageData::List);
+    }
+    MessageData::Other
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for MessageArgs {
+    fn drop(&mut self) {
+        use wasm_bindgen::JsCast;
+
+        if let Some(window) = web_sys::window() {
+            let _ = window.remove_event_listener_with_callback(
+                "message",
+                self.listener.as_ref().unchecked_ref(),
+            );
+            if let Some((handle, _)) = &self.timer {
+                window.clear_timeout_with_handle(*handle);
+            }
+        }
+    }
+}
+
+/// The url args posted by the parent frame, with
+/// [`ArgSource::PostMessage`](crate::ArgSource::PostMessage).  `None` on
+/// timeout and for other sources.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn posted_args() -> Option<Vec<String>> {
+    if let Some(timeout_ms) = crate::source::post_message_timeout() {
+        if let Some(window) = web_sys::window() {
+            return listen(&window, timeout_ms).await;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::future::block_on;
+
+    #[test]
+    fn args_from_messages() {
+        let page = "https://example.org/app";
+        assert_eq!(
+            message_args(page, MessageData::Text("verbose&name=Jo+Jo".into())),
+            Some(vec![page.into(), "verbose".into(), "name=Jo Jo".into()])
+        );
+        assert_eq!(
+            message_args(page, MessageData::List(vec!["name=Jo+Jo".into()])),
+            Some(vec![page.into(), "name=Jo+Jo".into()])
+        );
+        assert_eq!(message_args(page, MessageData::Other), None);
+    }
+
+    #[test]
+    fn first_settles() {
+        let settled: Rc<RefCell<Settled>> = Rc::default();
+        settled.borrow_mut().settle(Some(vec!["/".into()]));
+        settled.borrow_mut().settle(None);
+        let args = block_on(MessageArgs {
+            settled: Rc::clone(&settled),
+        });
+        assert_eq!(args, Some(vec!["/".to_string()]));
+    }
+}
@@ -0,0 +1,98 @@
+//! # Parse results that never exit
+//!
+//! For apps that live on, a game loop or a long lived web page, where
+//! printing and exiting on help is not wanted.
+
+use clap::error::{Error, ErrorKind};
+
+/// # What a parse came to
+///
+/// Returned by [`WeParser::we_parse_outcome()`](crate::WeParser::we_parse_outcome()),
+/// or from any clap result with [`we_classify`].
+/// Help and version are split out of the error so they can be shown in
+/// the app, nothing has been printed.  The texts are plain, without ANSI
+/// colors.
+#[derive(Debug)]
+pub enum WeOutcome<T> {
+    /// The args parsed.
+    Parsed(T),
+    /// Help was requested, with the help text.
+    Help(String),
+    /// Version was requested, with the version text.
+    Version(String),
+    /// The args didn't parse.  This includes
+    /// [`ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand`], a failure
+    /// that shows help.
+    Error(Error),
+}
+
+impl<T> From<Result<T, Error>> for WeOutcome<T> {
+    fn from(result: Result<T, Error>) -> Self {
+        match result {
+            Ok(opts) => Self::Parsed(opts),
+            Err(err) => match err.kind() {
+                ErrorKind::DisplayHelp => Self::Help(err.to_string()),
+                ErrorKind::DisplayVersion => Self::Version(err.to_string()),
+                _ => Self::Error(err),
+            },
+        }
+    }
+}
+
+/// # Classify a parse result
+///
+/// Turns the result of a `try` function, like
+/// [`WeParser::we_try_parse()`](crate::WeParser::we_try_parse()) or
+/// [`WeCommand::we_try_get_matches()`](crate::WeCommand::we_try_get_matches()),
+/// into a [`WeOutcome`], so help and version can be matched on without
+/// checking the error kind.  The same as `result.into()`.
+///
+/// # Example
+/// ``` rust
+/// use clap::Command;
+/// use we_clap::{we_classify, WeOutcome};
+///
+/// let cli = Command::new("app").version("1.0");
+///
+/// match we_classify(cli.try_get_matches_from(["app", "--version"])) {
+///     WeOutcome::Version(text) => assert_eq!(text, "app 1.0\n"),
+///     _ => unreachable!(),
+/// }
+/// ```
+pub fn we_classify<T>(result: Result<T, Error>) -> WeOutcome<T> {
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser, Debug, PartialEq)]
+    #[command(name = "app", version = "1.2.3", about = "An app")]
+    struct Opts {
+        #[arg(long)]
+        name: String,
+    }
+
+    fn outcome(args: &[&str]) -> WeOutcome<Opts> {
+        we_classify(Opts::try_parse_from(args))
+    }
+
+    #[test]
+    fn outcomes() {
+        assert!(
+            matches!(outcome(&["app", "--name", "x"]), WeOutcome::Parsed(opts) if opts.name == "x")
+        );
+        assert!(
+            matches!(outcome(&["app", "--help"]), WeOutcome::Help(help) if help.contains("An app"))
+        );
+        assert!(
+            matches!(outcome(&["app", "-V"]), WeOutcome::Version(version) if version == "app 1.2.3\n")
+        );
+        assert!(matches!(
+            outcome(&["app"]),
+            WeOutcome::Error(err) if err.kind() == ErrorKind::MissingRequiredArgument
+        ));
+    }
+}
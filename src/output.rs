@@ -0,0 +1,832 @@
+//! # Output for clap messages on the web
+//!
+//! Help, version and error messages that clap would print and then exit
+//! on native are routed here on the web.  Help and version messages go to
+//! [`cliw::output::print`], everything else to [`cliw::output::eprint`],
+//! unless an [`OutputTarget`] is set.
+
+#[cfg(any(test, target_arch = "wasm32"))]
+use clap::builder::StyledStr;
+#[cfg(any(test, target_arch = "wasm32"))]
+use clap::error::{Error, ErrorKind};
+use std::cell::RefCell;
+use std::fmt;
+
+/// # Name of the DOM event for parse errors on the web
+///
+/// With the "web-dom-events" feature a [`CustomEvent`] with this name is
+/// dispatched on `window` for every parse error on the web.  The event's
+/// `detail` is the error message as a string, the same text sent to the
+/// console or popup alert.  Help and version don't dispatch events.
+///
+/// ``` js
+/// window.addEventListener("weclap:error", (event) => showError(event.detail));
+/// ```
+///
+/// [`CustomEvent`]: https://developer.mozilla.org/en-US/docs/Web/API/CustomEvent
+pub const ERROR_EVENT: &str = "weclap:error";
+
+/// A registered message callback.
+type Callback = Box<dyn Fn(&str)>;
+
+/// A registered output sink.
+type Sink = Box<dyn Fn(&str, OutputKind)>;
+
+thread_local! {
+    static ON_VERSION: RefCell<Option<Callback>> = RefCell::new(None);
+    static TARGET: RefCell<Option<OutputTarget>> = const { RefCell::new(None) };
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+thread_local! {
+    static ALERTS: RefCell<AlertBuffer> = const { RefCell::new(AlertBuffer::new()) };
+}
+
+#[cfg(any(test, feature = "test-util"))]
+thread_local! {
+    static CAPTURE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// # Where web output goes
+///
+/// Chosen at runtime with [`set_output_target`], instead of by the
+/// "web-alert" and "web-console" features.  Help, version and error
+/// messages from [`WeParser`](crate::WeParser) and
+/// [`WeCommand`](crate::WeCommand) all go to the target.  Console, alert
+/// and element targets need the "web-output-target" feature, without it
+/// they use the feature chosen output.  Not used on native, where output
+/// is to stdout and stderr.
+pub enum OutputTarget {
+    /// The browser console, errors to `console.error`.
+    Console,
+    /// A popup alert.
+    Alert,
+    /// The text content of the element with this id, a `<pre>` keeps the
+    /// lines of help.  Each message replaces the last.  With no such
+    /// element the feature chosen output is used, after a console warning.
+    Element(String),
+    /// Like [`Element`](OutputTarget::Element), but each message is
+    /// appended as a line, for a log of everything printed.
+    ElementAppend(String),
+    /// A function given each message and its kind.  It must not set the
+    /// output target.  See [`set_output_callback`].
+    Callback(Sink),
+}
+
+/// # The kind of a web output message
+///
+/// Given to an [`OutputTarget::Callback`] with each message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputKind {
+    /// Help and version, what clap prints to stdout on native.
+    Stdout,
+    /// Errors, what clap prints to stderr on native.
+    Stderr,
+}
+
+impl fmt::Debug for OutputTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Console => write!(f, "Console"),
+            Self::Alert => write!(f, "Alert"),
+            Self::Element(id) => f.debug_tuple("Element").field(id).finish(),
+            Self::ElementAppend(id) => f.debug_tuple("ElementAppend").field(id).finish(),
+            Self::Callback(_) => write!(f, "Callback(..)"),
+        }
+    }
+}
+
+/// # Set where web output goes
+///
+/// Replaces any target set before.  See [`OutputTarget`].
+///
+/// # Example
+/// ``` rust
+/// use we_clap::OutputTarget;
+///
+/// we_clap::set_output_target(OutputTarget::Element("messages".into()));
+/// ```
+pub fn set_output_target(target: OutputTarget) {
+    TARGET.with(|current| *current.borrow_mut() = Some(target));
+}
+
+/// # Send web output to your own function
+///
+/// Help, version and error messages are given to `callback` with their
+/// [`OutputKind`], with ANSI colors stripped, instead of going to the
+/// console or popup alert.  For a virtual terminal widget or the text
+/// display of any front-end framework.  The same as setting an
+/// [`OutputTarget::Callback`], it replaces any target set before.  Help
+/// printing still returns `Ok(())`.  Not used on native, where output is to
+/// stdout and stderr.
+///
+/// # Example
+/// ``` rust
+/// use we_clap::OutputKind;
+///
+/// we_clap::set_output_callback(|msg, kind| match kind {
+///     OutputKind::Stdout => println!("{msg}"),
+///     OutputKind::Stderr => eprintln!("{msg}"),
+/// });
+/// ```
+pub fn set_output_callback(callback: impl Fn(&str, OutputKind) + 'static) {
+    set_output_target(OutputTarget::Callback(Box::new(callback)));
+}
+
+/// # Register a callback for version requests on the web
+///
+/// The callback is given the rendered version message whenever a version
+/// type argument short circuits parsing on the web.  It is called after the
+/// message has been sent to the console or popup alert, just before exit.
+///
+/// With [`clap::Command::propagate_version`] every subcommand accepts
+/// `--version`.  Clap reports a version request at any subcommand level with
+/// the same
+/// [`ErrorKind::DisplayVersion`](clap::error::ErrorKind::DisplayVersion),
+/// so `?sub&--version` is handled exactly like `?--version`.  The message names the subcommand, for example
+/// `demo-sub 1.0.0`.
+///
+/// Registering a new callback replaces the previous one.  Not used on native,
+/// where clap prints the version itself.
+///
+/// # Example
+/// ``` rust
+/// we_clap::we_on_version(|msg| {
+///     // Show the version in your about box.
+///     let _ = msg;
+/// });
+/// ```
+pub fn we_on_version(callback: impl Fn(&str) + 'static) {
+    ON_VERSION.with(|on_version| *on_version.borrow_mut() = Some(Box::new(callback)));
+}
+
+/// # List the active output sinks
+///
+/// Names of the places help, version and error messages are sent to, as
+/// decided by the [`OutputTarget`] set at runtime, or by the compiled
+/// features when none is set.  Handy for logging the effective
+/// configuration at startup, for example to catch both `web-alert` and
+/// `web-console` being enabled when only one was expected.
+///
+/// * `"callback"` : the [`OutputTarget::Callback`], or
+///   [`set_output_callback`].
+/// * `"element"` : an [`OutputTarget::Element`] or
+///   [`OutputTarget::ElementAppend`], with the web-output-target feature.
+/// * `"alert"` : popup alert, the [`OutputTarget::Alert`] with the
+///   web-output-target feature, or with no target the web-alert feature.
+/// * `"console"` : browser console, the [`OutputTarget::Console`] with the
+///   web-output-target feature, or with no target the web-console feature.
+/// * `"std"` : standard output and error, always used on native.
+///
+/// On the web an empty list means messages are silently dropped.  An
+/// element target whose element is missing falls back to the features.
+///
+/// # Example
+/// ``` rust
+/// let sinks = we_clap::active_sinks();
+/// if sinks.len() > 1 {
+///     eprintln!("we_clap output goes to {sinks:?}");
+/// }
+/// ```
+#[must_use]
+pub fn active_sinks() -> Vec<&'static str> {
+    if cfg!(target_arch = "wasm32") {
+        web_sinks()
+    } else {
+        vec!["std"]
+    }
+}
+
+/// The web sinks, of the output target or else the features.
+fn web_sinks() -> Vec<&'static str> {
+    let target = TARGET.with(|target| target.borrow().as_ref().and_then(target_sink));
+    target.map_or_else(feature_sinks, |sink| vec![sink])
+}
+
+/// The sink of an output target, `None` where it uses the features.
+fn target_sink(target: &OutputTarget) -> Option<&'static str> {
+    match target {
+        OutputTarget::Callback(_) => Some("callback"),
+        _ if !cfg!(feature = "web-output-target") => None,
+        OutputTarget::Console => Some("console"),
+        OutputTarget::Alert => Some("alert"),
+        OutputTarget::Element(_) | OutputTarget::ElementAppend(_) => Some("element"),
+    }
+}
+
+/// The web sinks chosen by the features.
+fn feature_sinks() -> Vec<&'static str> {
+    let mut sinks = Vec::new();
+    if cfg!(feature = "web-alert") {
+        sinks.push("alert");
+    }
+    if cfg!(feature = "web-console") {
+        sinks.push("console");
+    }
+    sinks
+}
+
+/// Send a short circuiting clap error to the web output.
+///
+/// Help and version go to regular output, errors to error output, see
+/// [`is_failure`].  Version messages are also handed to the
+/// [`we_on_version`] callback.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) fn report(err: &Error) {
+    batched(|| report_now(err));
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+fn report_now(err: &Error) {
+    let msg = format!("{err}");
+    if is_failure(err) {
+        eprint(&styled(&err.render()));
+        #[cfg(all(target_arch = "wasm32", feature = "web-dom-events"))]
+        dispatch_error(&msg);
+        return;
+    }
+    print(&styled(&err.render()));
+    if err.kind() == ErrorKind::DisplayVersion {
+        ON_VERSION.with(|on_version| {
+            if let Some(callback) = on_version.borrow().as_ref() {
+                callback(&msg);
+            }
+        });
+    }
+}
+
+/// A genuine failure, for error output and `console.error`.  Like clap on
+/// native, only [`ErrorKind::DisplayHelp`] and
+/// [`ErrorKind::DisplayVersion`] are not, even
+/// [`ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand`] is.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn is_failure(err: &Error) -> bool {
+    err.use_stderr()
+}
+
+/// Print a message, to stdout on native and the [`OutputTarget`] on the web.
+pub(crate) fn print(msg: &str) {
+    write(msg, false);
+}
+
+/// Print an error message, to stderr on native and the [`OutputTarget`] on
+/// the web.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) fn eprint(msg: &str) {
+    write(msg, true);
+}
+
+/// # Capture output in tests
+///
+/// Runs `f` and returns everything it printed through we_clap, help,
+/// version and error messages, instead of sending it to the console or
+/// popup alert.  ANSI colors are stripped and each message ends with a
+/// newline.  For `wasm-bindgen-test` cases that parse `--help` and check
+/// the help text.  On native it captures what would go to stdout and
+/// stderr, except native help and errors that clap prints itself.
+///
+/// Captures nest, the output of an inner capture is not in the outer one.
+///
+/// (With "test-util" feature)
+///
+/// # Example
+/// ``` rust
+/// use clap::Command;
+/// use we_clap::WeCommand;
+///
+/// let output = we_clap::capture_output(|| {
+///     Command::new("demo").version("1.2.3").we_print_version();
+/// });
+/// assert_eq!(output, "demo 1.2.3\n");
+/// ```
+#[cfg(any(test, feature = "test-util"))]
+pub fn capture_output(f: impl FnOnce()) -> String {
+    /// Puts back the outer capture, even if `f` panics.
+    struct Restore(Option<String>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CAPTURE.with_borrow_mut(|capture| *capture = self.0.take());
+        }
+    }
+
+    let _restore = Restore(CAPTURE.with_borrow_mut(|capture| capture.replace(String::new())));
+    f();
+    CAPTURE
+        .with_borrow_mut(|capture| capture.take())
+        .unwrap_or_default()
+}
+
+/// Add a message to the capture buffer, if capturing.
+#[cfg(any(test, feature = "test-util"))]
+fn captured(msg: &str) -> bool {
+    CAPTURE.with_borrow_mut(|capture| match capture {
+        Some(buffer) => {
+            push_line(buffer, &strip_ansi(msg));
+            true
+        }
+        None => false,
+    })
+}
+
+fn write(msg: &str, error: bool) {
+    #[cfg(any(test, feature = "test-util"))]
+    if captured(msg) {
+        return;
+    }
+    #[cfg(target_arch = "wasm32")]
+    TARGET.with(|target| match target.borrow().as_ref() {
+        Some(target) => deliver(target, msg, error),
+        None => fallback(msg, error),
+    });
+    #[cfg(not(target_arch = "wasm32"))]
+    fallback(msg, error);
+}
+
+/// Whether web output goes to the console.
+#[cfg(all(target_arch = "wasm32", feature = "web-console-groups"))]
+pub(crate) fn to_console() -> bool {
+    TARGET.with(|target| match target.borrow().as_ref() {
+        None => cfg!(feature = "web-console"),
+        Some(OutputTarget::Console) => cfg!(feature = "web-output-target"),
+        Some(_) => false,
+    })
+}
+
+/// Send a message to the target.
+///
+/// Only the console gets ANSI colors, translated with "web-console-styled".
+#[cfg(any(test, target_arch = "wasm32"))]
+fn deliver(target: &OutputTarget, msg: &str, error: bool) {
+    match target {
+        OutputTarget::Callback(callback) => {
+            let kind = if error {
+                OutputKind::Stderr
+            } else {
+                OutputKind::Stdout
+            };
+            callback(&strip_ansi(msg), kind);
+        }
+        #[cfg(all(target_arch = "wasm32", feature = "web-output-target"))]
+        OutputTarget::Console => {
+            #[cfg(feature = "web-console-styled")]
+            console_styled(msg, error);
+            #[cfg(not(feature = "web-console-styled"))]
+            {
+                let msg = wasm_bindgen::JsValue::from_str(msg);
+                if error {
+                    web_sys::console::error_1(&msg);
+                } else {
+                    web_sys::console::log_1(&msg);
+                }
+            }
+        }
+        #[cfg(all(target_arch = "wasm32", feature = "web-output-target"))]
+        OutputTarget::Alert => alert(&strip_ansi(msg)),
+        #[cfg(all(target_arch = "wasm32", feature = "web-output-target"))]
+        OutputTarget::Element(id) => match element(id) {
+            Some(element) => element.set_text_content(Some(&strip_ansi(msg))),
+            None => fallback(msg, error),
+        },
+        #[cfg(all(target_arch = "wasm32", feature = "web-output-target"))]
+        OutputTarget::ElementAppend(id) => match element(id) {
+            Some(element) => {
+                let mut text = element.text_content().unwrap_or_default();
+                push_line(&mut text, &strip_ansi(msg));
+                element.set_text_content(Some(&text));
+            }
+            None => fallback(msg, error),
+        },
+        #[cfg(not(all(target_arch = "wasm32", feature = "web-output-target")))]
+        _ => fallback(msg, error),
+    }
+}
+
+/// The feature chosen output.
+///
+/// On the web errors go to `console.error` and everything else to
+/// `console.log`, with "web-console".  A popup alert can't show ANSI
+/// escape codes, it gets the message with them stripped.  The console gets
+/// it as is, or colored with "web-console-styled".  With both, as with
+/// "web-both", the console is written first so the detail is there while
+/// the alert shows.
+fn fallback(msg: &str, error: bool) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        #[cfg(feature = "web-console-styled")]
+        console_styled(msg, error);
+        #[cfg(all(feature = "web-console", not(feature = "web-console-styled")))]
+        {
+            use std::io::Write;
+            let _ = if error {
+                cliw::output::console_error().write(msg.as_bytes())
+            } else {
+                cliw::output::console_log().write(msg.as_bytes())
+            };
+        }
+        #[cfg(feature = "web-alert")]
+        alert(&strip_ansi(msg));
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if error {
+        cliw::output::eprint(msg);
+    } else {
+        cliw::output::print(msg);
+    }
+}
+
+/// # Alert text held back for one popup
+///
+/// Every `alert` is a popup the user must dismiss before the next, so an
+/// error and a hint would be two.  While a batch is open alert messages
+/// are collected here, and the outermost batch shows them in one popup
+/// when it closes.
+#[cfg(any(test, target_arch = "wasm32"))]
+struct AlertBuffer {
+    depth: usize,
+    text: String,
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+impl AlertBuffer {
+    const fn new() -> Self {
+        Self {
+            depth: 0,
+            text: String::new(),
+        }
+    }
+
+    fn open(&mut self) {
+        self.depth += 1;
+    }
+
+    /// Close a batch, with the text to show if it was the outermost.
+    fn close(&mut self) -> Option<String> {
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth > 0 || self.text.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut self.text))
+    }
+
+    /// Collect a message in an open batch, or give it back to show now.
+    #[cfg(any(
+        test,
+        all(
+            target_arch = "wasm32",
+            any(feature = "web-alert", feature = "web-output-target")
+        )
+    ))]
+    fn push(&mut self, msg: &str) -> Option<String> {
+        if self.depth == 0 {
+            return Some(msg.to_string());
+        }
+        if !self.text.is_empty() && !self.text.ends_with('\n') {
+            self.text.push('\n');
+        }
+        self.text.push_str(msg);
+        None
+    }
+}
+
+/// Run `f` with alerts batched, so everything it sends to a popup alert
+/// shows in one popup at the end.
+///
+/// The popup shows before returning, so before any exit that follows.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) fn batched<R>(f: impl FnOnce() -> R) -> R {
+    ALERTS.with_borrow_mut(AlertBuffer::open);
+    let result = f();
+    if let Some(text) = ALERTS.with_borrow_mut(AlertBuffer::close) {
+        show_alert(&text);
+    }
+    result
+}
+
+/// Send a message to a popup alert, or the open batch.
+#[cfg(all(
+    target_arch = "wasm32",
+    any(feature = "web-alert", feature = "web-output-target")
+))]
+fn alert(msg: &str) {
+    if let Some(msg) = ALERTS.with_borrow_mut(|alerts| alerts.push(msg)) {
+        show_alert(&msg);
+    }
+}
+
+/// Show a popup alert.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn show_alert(text: &str) {
+    #[cfg(target_arch = "wasm32")]
+    if let Some(window) = web_sys::window() {
+        let _ = window.alert_with_message(text);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = text;
+}
+
+/// Log ANSI colored text to the console as `%c` styled text.
+#[cfg(all(target_arch = "wasm32", feature = "web-console-styled"))]
+fn console_styled(msg: &str, error: bool) {
+    let (format, css) = crate::style::ansi_to_console_css(msg);
+    let args = js_sys::Array::new();
+    args.push(&js_sys::JsString::from(format));
+    for css in css {
+        args.push(&js_sys::JsString::from(css));
+    }
+    if error {
+        web_sys::console::error(&args);
+    } else {
+        web_sys::console::log(&args);
+    }
+}
+
+/// Clap styled text for the web output, with ANSI colors for the
+/// "web-console-styled" feature or a `?color=always`.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) fn styled(text: &StyledStr) -> String {
+    if cfg!(target_arch = "wasm32") && crate::style::colored(cfg!(feature = "web-console-styled")) {
+        text.ansi().to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Add a message to text as whole lines, ending with a newline.
+#[cfg(any(
+    test,
+    feature = "test-util",
+    all(target_arch = "wasm32", feature = "web-output-target")
+))]
+fn push_line(text: &mut String, msg: &str) {
+    text.push_str(msg);
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+}
+
+/// The element with the id, or `None` after a console warning.
+#[cfg(all(
+    target_arch = "wasm32",
+    any(feature = "web-output-target", feature = "web-help-element")
+))]
+pub(crate) fn element(id: &str) -> Option<web_sys::Element> {
+    let element = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(id));
+    if element.is_none() {
+        web_sys::console::warn_1(&wasm_bindgen::JsValue::from_str(&missing_element(id)));
+    }
+    element
+}
+
+/// The warning for a missing output element.
+#[cfg(any(
+    test,
+    all(
+        target_arch = "wasm32",
+        any(feature = "web-output-target", feature = "web-help-element")
+    )
+))]
+fn missing_element(id: &str) -> String {
+    format!("we_clap: no element with id `{id}`, using the default output")
+}
+
+/// Remove ANSI escape sequences, like the SGR codes that color clap's
+/// output, from a message.
+#[cfg(any(test, feature = "test-util", target_arch = "wasm32"))]
+fn strip_ansi(msg: &str) -> String {
+    let mut plain = String::with_capacity(msg.len());
+    let mut chars = msg.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            plain.push(c);
+            continue;
+        }
+        // A control sequence is `ESC [`, parameters, then a final byte.
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    plain
+}
+
+/// Dispatch the [`ERROR_EVENT`] on `window` with the message as detail.
+#[cfg(all(target_arch = "wasm32", feature = "web-dom-events"))]
+fn dispatch_error(msg: &str) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let init = web_sys::CustomEventInit::new();
+    init.set_detail(&wasm_bindgen::JsValue::from_str(msg));
+    if let Ok(event) = web_sys::CustomEvent::new_with_event_init_dict(ERROR_EVENT, &init) {
+        let _ = window.dispatch_event(&event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Command;
+    use cliw::url_args::UrlArgs;
+    use std::rc::Rc;
+
+    fn versioned() -> Command {
+        Command::new("demo")
+            .version("1.2.3")
+            .propagate_version(true)
+            .subcommand(Command::new("sub").subcommand(Command::new("deep")))
+    }
+
+    #[test]
+    fn sinks_follow_target() {
+        if cfg!(not(target_arch = "wasm32")) {
+            assert_eq!(active_sinks(), ["std"]);
+        }
+        let sinks = web_sinks();
+        assert_eq!(sinks.contains(&"alert"), cfg!(feature = "web-alert"));
+        assert_eq!(sinks.contains(&"console"), cfg!(feature = "web-console"));
+
+        set_output_callback(|_, _| {});
+        assert_eq!(web_sinks(), ["callback"]);
+
+        set_output_target(OutputTarget::ElementAppend("log".into()));
+        if cfg!(feature = "web-output-target") {
+            assert_eq!(web_sinks(), ["element"]);
+        } else {
+            assert_eq!(web_sinks(), feature_sinks());
+        }
+        TARGET.with(|target| *target.borrow_mut() = None);
+        assert_eq!(web_sinks(), feature_sinks());
+    }
+
+    #[test]
+    fn one_alert_per_batch() {
+        let mut alerts = AlertBuffer::new();
+        assert_eq!(alerts.push("now"), Some("now".to_string()));
+
+        alerts.open();
+        assert_eq!(alerts.push("error: nope"), None);
+        alerts.open();
+        assert_eq!(alerts.push("Usage: demo\n"), None);
+        assert_eq!(alerts.close(), None);
+        assert_eq!(alerts.push("hint"), None);
+        assert_eq!(
+            alerts.close(),
+            Some("error: nope\nUsage: demo\nhint".to_string())
+        );
+
+        alerts.open();
+        assert_eq!(alerts.close(), None);
+    }
+
+    #[test]
+    fn capture() {
+        let output = capture_output(|| {
+            print("\x1b[1mUsage:\x1b[0m demo");
+            let inner = capture_output(|| eprint("inner\n"));
+            assert_eq!(inner, "inner\n");
+            eprint("error: nope\n");
+        });
+        assert_eq!(output, "Usage: demo\nerror: nope\n");
+
+        let err = versioned()
+            .try_get_matches_from(["demo", "--version"])
+            .unwrap_err();
+        assert_eq!(capture_output(|| report(&err)), "demo 1.2.3\n");
+    }
+
+    #[test]
+    fn version_on_subcommand() {
+        let seen = Rc::new(RefCell::new(String::new()));
+        let sink = Rc::clone(&seen);
+        we_on_version(move |msg| sink.borrow_mut().push_str(msg));
+
+        let url = "http://example.org/index.html?sub&deep&--version";
+        let err = versioned()
+            .try_get_matches_from(UrlArgs::from(url))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DisplayVersion);
+
+        report(&err);
+        assert!(seen.borrow().contains("deep 1.2.3"));
+    }
+
+    #[test]
+    fn callback_target() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&seen);
+        let target = OutputTarget::Callback(Box::new(move |msg, kind| {
+            sink.borrow_mut().push((msg.to_string(), kind));
+        }));
+        assert_eq!(format!("{target:?}"), "Callback(..)");
+
+        deliver(&target, "help", false);
+        deliver(&target, "error", true);
+        assert_eq!(
+            *seen.borrow(),
+            [
+                ("help".to_string(), OutputKind::Stdout),
+                ("error".to_string(), OutputKind::Stderr)
+            ]
+        );
+
+        set_output_callback(|_, _| {});
+        TARGET.with(|target| {
+            assert!(matches!(
+                target.borrow().as_ref(),
+                Some(OutputTarget::Callback(_))
+            ));
+        });
+        TARGET.with(|target| *target.borrow_mut() = None);
+    }
+
+    #[test]
+    fn element_lines() {
+        let mut text = String::new();
+        push_line(&mut text, "error: nope");
+        push_line(&mut text, "Usage: demo\n");
+        push_line(&mut text, "");
+        assert_eq!(text, "error: nope\nUsage: demo\n");
+        assert_eq!(
+            missing_element("cli-output"),
+            "we_clap: no element with id `cli-output`, using the default output"
+        );
+        assert_eq!(
+            format!("{:?}", OutputTarget::ElementAppend("log".into())),
+            "ElementAppend(\"log\")"
+        );
+    }
+
+    #[test]
+    fn ansi_stripped() {
+        let help = Command::new("demo")
+            .arg(clap::Arg::new("name").long("name"))
+            .color(clap::ColorChoice::Always)
+            .render_help();
+        let colored = format!("{}", help.ansi());
+        assert!(colored.contains('\x1b'));
+        assert_eq!(strip_ansi(&colored), format!("{help}"));
+
+        assert_eq!(strip_ansi("\x1b[1;4mUsage:\x1b[0m demo"), "Usage: demo");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn console_colored_alert_plain() {
+        let err = Command::new("app")
+            .color(clap::ColorChoice::Always)
+            .try_get_matches_from(["app", "--nope"])
+            .unwrap_err();
+        let console = err.render().ansi().to_string();
+        assert!(console.contains('\x1b'));
+        assert_eq!(strip_ansi(&console), err.render().to_string());
+    }
+
+    #[test]
+    fn failures_to_error_output() {
+        let command = Command::new("demo")
+            .version("1.0")
+            .arg_required_else_help(true)
+            .arg(clap::Arg::new("name").long("name"));
+        let kind_and_failure = |args: &[&str]| {
+            let err = command.clone().try_get_matches_from(args).unwrap_err();
+            (err.kind(), is_failure(&err))
+        };
+
+        assert_eq!(
+            kind_and_failure(&["demo", "--help"]),
+            (ErrorKind::DisplayHelp, false)
+        );
+        assert_eq!(
+            kind_and_failure(&["demo", "--version"]),
+            (ErrorKind::DisplayVersion, false)
+        );
+        assert_eq!(
+            kind_and_failure(&["demo"]),
+            (ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand, true)
+        );
+        assert_eq!(
+            kind_and_failure(&["demo", "--nope"]),
+            (ErrorKind::UnknownArgument, true)
+        );
+    }
+
+    #[test]
+    fn help_is_not_version() {
+        let seen = Rc::new(RefCell::new(false));
+        let sink = Rc::clone(&seen);
+        we_on_version(move |_| *sink.borrow_mut() = true);
+
+        let url = "http://example.org/index.html?sub&--help";
+        let err = versioned()
+            .try_get_matches_from(UrlArgs::from(url))
+            .unwrap_err();
+        report(&err);
+        assert!(!*seen.borrow());
+    }
+}
@@ -0,0 +1,97 @@
+//! # Panic messages on the web
+//!
+//! A panic in wasm, like a clap debug assert about a broken command, only
+//! shows as an `unreachable` trap in the console.  With the "web-panic-hook"
+//! feature the first web parse installs a panic hook that sends the panic
+//! message to the web error output first, the
+//! [`OutputTarget`](crate::OutputTarget) where help and errors go.
+//! [`install_panic_alert`] installs it without the feature.  Any hook set
+//! before is still called after.
+
+#[cfg(any(test, target_arch = "wasm32"))]
+use std::panic::PanicHookInfo;
+#[cfg(any(test, target_arch = "wasm32"))]
+use std::sync::Once;
+
+#[cfg(any(test, target_arch = "wasm32"))]
+static INSTALL: Once = Once::new();
+
+/// Install the panic hook once, when the "web-panic-hook" feature is on.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn install() {
+    #[cfg(feature = "web-panic-hook")]
+    install_with(crate::output::eprint);
+}
+
+/// # Show panics where help and errors go
+///
+/// Sets a panic hook that sends the panic message, with its location, to
+/// the web error output first, the [`OutputTarget`](crate::OutputTarget)
+/// or [output callback](crate::set_output_callback) where help and errors
+/// go.  A panic deep in a value parser then shows in the console or a
+/// popup alert instead of a blank page.  The hook set before is still
+/// called after.  Installed once, later calls do nothing.
+///
+/// The "web-panic-hook" feature installs it with the first web parse.  On
+/// native this does nothing, the default hook prints panics.
+///
+/// # Example
+/// ``` rust
+/// we_clap::install_panic_alert();
+/// ```
+pub fn install_panic_alert() {
+    #[cfg(target_arch = "wasm32")]
+    install_with(crate::output::eprint);
+}
+
+/// Install a panic hook that gives the message to `output`, once.
+#[cfg(target_arch = "wasm32")]
+fn install_with(output: fn(&str)) {
+    INSTALL.call_once(|| chain(output));
+}
+
+/// Set a panic hook that gives the message to `output`, then calls the
+/// hook set before.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn chain(output: fn(&str)) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        output(&message(info));
+        previous(info);
+    }));
+}
+
+/// The panic message with its location, like the default hook prints.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn message(info: &PanicHookInfo) -> String {
+    format!("{info}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    static SEEN: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    fn record(msg: &str) {
+        SEEN.lock().unwrap().push(msg.to_string());
+    }
+
+    #[test]
+    fn hook_sees_panic() {
+        let original = std::panic::take_hook();
+        chain(record);
+        let _ = std::panic::catch_unwind(|| panic!("debug assert failed"));
+        std::panic::set_hook(original);
+        assert!(!INSTALL.is_completed());
+
+        let seen = SEEN.lock().unwrap();
+        let ours: Vec<&String> = seen
+            .iter()
+            .filter(|msg| msg.contains("debug assert failed"))
+            .collect();
+        assert_eq!(ours.len(), 1);
+        assert!(ours[0].contains("panic_hook.rs"), "{}", ours[0]);
+    }
+}
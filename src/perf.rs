@@ -0,0 +1,110 @@
+//! # Performance timeline marks on the web
+//!
+//! With the "web-perf-marks" feature each phase of a web parse is wrapped
+//! in [`performance.mark`] and [`performance.measure`] calls, so browser
+//! devtools show the phases on the performance timeline.
+//!
+//! The names are stable.  A phase `weclap:parse` is marked
+//! `weclap:parse:start` and `weclap:parse:end` and measured as
+//! `weclap:parse`.  The phases are:
+//!
+//! * `weclap:read`, reading the url.
+//! * `weclap:translate`, translating the url into command line arguments.
+//! * `weclap:parse`, clap parsing the arguments.
+//!
+//! [`performance.mark`]: https://developer.mozilla.org/en-US/docs/Web/API/Performance/mark
+//! [`performance.measure`]: https://developer.mozilla.org/en-US/docs/Web/API/Performance/measure
+
+/// Reading the url.
+#[cfg(target_arch = "wasm32")]
+pub(crate) const READ: &str = "weclap:read";
+/// Translating the url into command line arguments.
+#[cfg(target_arch = "wasm32")]
+pub(crate) const TRANSLATE: &str = "weclap:translate";
+/// Clap parsing the arguments.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) const PARSE: &str = "weclap:parse";
+
+/// Somewhere to put timing marks.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-perf-marks")))]
+trait Timeline {
+    fn mark(&self, name: &str);
+    fn measure(&self, name: &str, start: &str, end: &str);
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-perf-marks"))]
+impl Timeline for web_sys::Performance {
+    fn mark(&self, name: &str) {
+        let _ = web_sys::Performance::mark(self, name);
+    }
+    fn measure(&self, name: &str, start: &str, end: &str) {
+        let _ = self.measure_with_start_mark_and_end_mark(name, start, end);
+    }
+}
+
+/// Run one phase of a web parse, marked on the performance timeline when
+/// the "web-perf-marks" feature is on.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn timed<R>(phase: &str, f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "web-perf-marks")]
+    if let Some(performance) = web_sys::window().and_then(|window| window.performance()) {
+        return timed_on(&performance, phase, f);
+    }
+    #[cfg(not(feature = "web-perf-marks"))]
+    let _ = phase;
+    f()
+}
+
+/// Run `f` between the start and end marks of a phase, then measure it.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-perf-marks")))]
+fn timed_on<R>(timeline: &impl Timeline, phase: &str, f: impl FnOnce() -> R) -> R {
+    let start = format!("{phase}:start");
+    let end = format!("{phase}:end");
+    timeline.mark(&start);
+    let result = f();
+    timeline.mark(&end);
+    timeline.measure(phase, &start, &end);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct Recorder(RefCell<Vec<String>>);
+
+    impl Timeline for Recorder {
+        fn mark(&self, name: &str) {
+            self.0.borrow_mut().push(format!("mark {name}"));
+        }
+        fn measure(&self, name: &str, start: &str, end: &str) {
+            self.0
+                .borrow_mut()
+                .push(format!("measure {name} {start} {end}"));
+        }
+    }
+
+    #[test]
+    fn marks_around_parse() {
+        use crate::translate::tests::url_args;
+        use clap::{Arg, Command};
+
+        let recorder = Recorder::default();
+        let command = Command::new("app").arg(Arg::new("name").long("name"));
+        let matches = timed_on(&recorder, PARSE, || {
+            command.try_get_matches_from(url_args("/?name=bob"))
+        });
+
+        assert!(matches.is_ok());
+        assert_eq!(
+            *recorder.0.borrow(),
+            [
+                "mark weclap:parse:start",
+                "mark weclap:parse:end",
+                "measure weclap:parse weclap:parse:start weclap:parse:end",
+            ]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! # Query string translation core
+//!
+//! The url to command line translation rules, free of the DOM and of `std`.
+//! Everything here needs only `core` and `alloc`, so it can be reused in a
+//! `no_std` wasm embedding that gets its query strings some other way.
+//!
+//! [`UrlArgs`](cliw::url_args::UrlArgs), clap and the [`Config`](crate::Config)
+//! are kept out, they live in the std layer.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// # Rules for translating query strings
+///
+/// The translation part of the [`Config`](crate::Config), borrowed.
+/// `no_std`.
+///
+/// # Example
+/// ``` rust
+/// let rules = we_clap::QueryRules::default();
+/// assert_eq!(rules.positional_key, "_");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueryRules<'a> {
+    /// See [`Config::use_equals_form`](crate::Config::use_equals_form).
+    pub use_equals_form: bool,
+    /// See [`Config::positional_key`](crate::Config::positional_key).
+    pub positional_key: &'a str,
+    /// See [`Config::positional_delimiter`](crate::Config::positional_delimiter).
+    pub positional_delimiter: Option<char>,
+}
+
+impl Default for QueryRules<'_> {
+    fn default() -> Self {
+        Self {
+            use_equals_form: true,
+            positional_key: "_",
+            positional_delimiter: None,
+        }
+    }
+}
+
+/// # Translate decoded query strings into command line arguments
+///
+/// The rules the web parsers use, without reading the url.  Each query
+/// string must already be percent decoded.  The program name, argv0, is
+/// not added.  `no_std`.
+///
+/// # Example
+/// ``` rust
+/// let rules = we_clap::QueryRules::default();
+/// let args = we_clap::we_translate_queries(["offset=-5", "n=bob", "_=file.txt"], &rules);
+/// assert_eq!(args, ["--offset=-5", "-n=bob", "file.txt"]);
+/// ```
+#[must_use]
+pub fn we_translate_queries<'q>(
+    queries: impl IntoIterator<Item = &'q str>,
+    rules: &QueryRules,
+) -> Vec<String> {
+    let mut args = Vec::new();
+    for query in queries {
+        translate_query(query, rules, &mut args);
+    }
+    args
+}
+
+/// Translate one query string into command line arguments.
+fn translate_query(query: &str, rules: &QueryRules, args: &mut Vec<String>) {
+    match query.split_once('=') {
+        Some((key, value)) if key == rules.positional_key => match rules.positional_delimiter {
+            Some(delimiter) => args.extend(value.split(delimiter).map(String::from)),
+            None => args.push(value.to_string()),
+        },
+        Some((key, value)) if !key.is_empty() && !key.starts_with('-') => {
+            if rules.use_equals_form {
+                args.push(format!("{}={value}", flag(key)));
+            } else {
+                args.push(flag(key));
+                args.push(value.to_string());
+            }
+        }
+        _ => args.push(query.to_string()),
+    }
+}
+
+/// The flag for a query key, short for one letter keys.
+pub(crate) fn flag(key: &str) -> String {
+    if key.chars().count() == 1 {
+        format!("-{key}")
+    } else {
+        format!("--{key}")
+    }
+}
@@ -0,0 +1,76 @@
+//! # The url query as a plain map
+//!
+//! For apps that want the query strings themselves, decoded the same way
+//! as for parsing, without clap.
+
+use cliw::url_args::UrlArgs;
+use std::collections::HashMap;
+
+/// # The webpage url query as a map
+///
+/// Like [`query_map_from`] for the url of the webpage.  On native there is
+/// no url and the map is empty.
+///
+/// # Example
+/// ``` rust
+/// let map = we_clap::query_map();
+/// let theme = map.get("theme").and_then(|values| values.first());
+/// ```
+#[must_use]
+pub fn query_map() -> HashMap<String, Vec<String>> {
+    map(crate::source::url_args())
+}
+
+/// # A url query as a map
+///
+/// Each query string is percent decoded, like for parsing, then split at
+/// the first `=` into key and value.  A query string without `=` is a key
+/// with an empty value.  A repeated key keeps every value, in url order.
+/// The path and any `#` fragment are ignored.
+///
+/// Decoding comes before splitting, so an encoded `%3D` in a key ends the
+/// key.  A `+` is a space, an encoded `%2B` is a plus.
+///
+/// # Example
+/// ``` rust
+/// let map = we_clap::query_map_from("https://example.org/?tag=a&tag=b%20c&verbose");
+///
+/// assert_eq!(map["tag"], ["a", "b c"]);
+/// assert_eq!(map["verbose"], [""]);
+/// ```
+#[must_use]
+pub fn query_map_from(url: &str) -> HashMap<String, Vec<String>> {
+    map(crate::source::from_url(url))
+}
+
+/// Collect the decoded query strings, skipping the path.
+fn map(url_args: UrlArgs) -> HashMap<String, Vec<String>> {
+    let mut map: HashMap<String, Vec<String>> = HashMap::new();
+    for query in url_args.skip(1) {
+        let query = query.to_string_lossy();
+        if query.is_empty() {
+            continue;
+        }
+        let (key, value) = query.split_once('=').unwrap_or((&query, ""));
+        map.entry(key.to_string())
+            .or_default()
+            .push(value.to_string());
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_and_encoded() {
+        let map = query_map_from("/app.html?na%20me=a%26b&n=1&&n=2&n=&--flag#n=3");
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(map["na me"], ["a&b"]);
+        assert_eq!(map["n"], ["1", "2", ""]);
+        assert_eq!(map["--flag"], [""]);
+        assert!(query_map().is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+//! # Control characters in the url
+//!
+//! A url can carry any character, percent encoded.  NUL, escape and other
+//! control characters in a value can garble the console or trip up code
+//! that trusts the args.  See [`Config::control_chars`](crate::Config::control_chars).
+
+use crate::WeError;
+
+/// # What to do with control characters from the url
+///
+/// Set in [`Config::control_chars`](crate::Config::control_chars).  Tab,
+/// line feed and carriage return are allowed, a text area value can hold
+/// them.  Every other [`char::is_control`] character counts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ControlChars {
+    /// Pass them to clap unchanged.
+    Keep,
+    /// Remove them from the args.  The default.
+    #[default]
+    Strip,
+    /// Refuse to parse, with [`WeError::InvalidCharacter`].  Functions that
+    /// return a [`clap::Error`] report it as an invalid value.
+    Reject,
+}
+
+/// # Check args for control characters
+///
+/// The guard used for [`ControlChars::Reject`], also handy as or in a
+/// validator for
+/// [`we_parse_async_validated`](crate::WeParser::we_parse_async_validated()).
+///
+/// # Errors
+///
+/// [`WeError::InvalidCharacter`] with the first offending arg, its control
+/// characters escaped so it is safe to print.
+///
+/// # Example
+/// ``` rust
+/// assert!(we_clap::we_check_chars(&["app".into(), "--name=bob".into()]).is_ok());
+/// assert!(we_clap::we_check_chars(&["app".into(), "--name=b\0b".into()]).is_err());
+/// ```
+pub fn we_check_chars(args: &[String]) -> Result<(), WeError> {
+    match args.iter().find(|arg| arg.chars().any(is_control)) {
+        Some(arg) => Err(WeError::InvalidCharacter(arg.escape_debug().to_string())),
+        None => Ok(()),
+    }
+}
+
+/// Strip or reject the control characters of the args, as `choice` says.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) fn sanitize(args: &mut [String], choice: ControlChars) -> Result<(), WeError> {
+    match choice {
+        ControlChars::Keep => {}
+        ControlChars::Strip => args.iter_mut().for_each(|arg| *arg = strip(arg)),
+        ControlChars::Reject => we_check_chars(args)?,
+    }
+    Ok(())
+}
+
+/// Remove the control characters from an arg.
+pub(crate) fn strip(arg: &str) -> String {
+    arg.chars().filter(|c| !is_control(*c)).collect()
+}
+
+/// A control character that isn't tab, line feed or carriage return.
+fn is_control(c: char) -> bool {
+    c.is_control() && !matches!(c, '\t' | '\n' | '\r')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translate::tests::url_args;
+
+    #[test]
+    fn control_chars() {
+        let args = url_args("/?name=b%00o%1Bb&note=two%0Alines");
+        assert_eq!(args, ["/", "--name=bob", "--note=two\nlines"]);
+
+        let args: Vec<String> = ["/", "--name=b\0ob"].map(String::from).into();
+        let err = we_check_chars(&args).unwrap_err();
+        assert!(matches!(err, WeError::InvalidCharacter(_)));
+        assert_eq!(
+            err.to_string(),
+            "invalid character in argument '--name=b\\0ob'"
+        );
+    }
+
+    #[test]
+    fn reject_mode() {
+        use crate::translate::url_to_args;
+        use crate::{set_config, Config};
+        use clap::Command;
+        use cliw::url_args::UrlArgs;
+
+        let command = Command::new("app");
+        set_config(Config {
+            control_chars: ControlChars::Reject,
+            ..Config::default()
+        });
+        let err = url_to_args(&command, UrlArgs::from("/?name=b%00b")).unwrap_err();
+        assert!(matches!(err, WeError::InvalidCharacter(arg) if arg == "--name=b\\0b"));
+
+        set_config(Config {
+            control_chars: ControlChars::Keep,
+            ..Config::default()
+        });
+        let args = url_to_args(&command, UrlArgs::from("/?name=b%00b")).unwrap();
+        assert_eq!(args, ["app", "--name=b\0b"]);
+        set_config(Config::default());
+    }
+}
@@ -0,0 +1,152 @@
+//! # Command lines as one string
+//!
+//! Args saved or pasted as a single string are split like a POSIX shell
+//! splits a command line, and joined with the quoting that splits back the
+//! same.
+
+use std::fmt;
+
+/// # Why a command line didn't split
+///
+/// From [`shell_split`].  Functions that return a [`clap::Error`] report
+/// it as an invalid value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitError {
+    /// A single quote without its closing quote.
+    UnbalancedSingleQuote,
+    /// A double quote without its closing quote.
+    UnbalancedDoubleQuote,
+    /// A backslash with nothing after it to escape.
+    TrailingBackslash,
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnbalancedSingleQuote => write!(f, "unbalanced single quote"),
+            Self::UnbalancedDoubleQuote => write!(f, "unbalanced double quote"),
+            Self::TrailingBackslash => write!(f, "trailing backslash"),
+        }
+    }
+}
+
+impl std::error::Error for SplitError {}
+
+/// # Split a command line into args
+///
+/// Splits like a POSIX shell, without expansions.  Whitespace separates
+/// args.  Single quotes keep everything up to the next single quote.
+/// Double quotes keep everything up to the next double quote, except that
+/// a backslash escapes `"`, `\`, `$` and `` ` ``.  A backslash outside
+/// quotes escapes the next character.  `''` is an empty arg.
+///
+/// # Errors
+///
+/// A [`SplitError`] for an unbalanced quote or a trailing backslash.
+///
+/// # Example
+/// ``` rust
+/// let args = we_clap::shell_split(r#"foo --bar "two words""#).unwrap();
+/// assert_eq!(args, ["foo", "--bar", "two words"]);
+///
+/// assert!(we_clap::shell_split("'open").is_err());
+/// ```
+pub fn shell_split(input: &str) -> Result<Vec<String>, SplitError> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut in_arg = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut arg));
+                    in_arg = false;
+                }
+                continue;
+            }
+            '\'' => loop {
+                match chars.next() {
+                    Some('\'') => break,
+                    Some(c) => arg.push(c),
+                    None => return Err(SplitError::UnbalancedSingleQuote),
+                }
+            },
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some(c @ ('"' | '\\' | '$' | '`')) => arg.push(c),
+                        Some(c) => {
+                            arg.push('\\');
+                            arg.push(c);
+                        }
+                        None => return Err(SplitError::UnbalancedDoubleQuote),
+                    },
+                    Some(c) => arg.push(c),
+                    None => return Err(SplitError::UnbalancedDoubleQuote),
+                }
+            },
+            '\\' => match chars.next() {
+                Some(c) => arg.push(c),
+                None => return Err(SplitError::TrailingBackslash),
+            },
+            c => arg.push(c),
+        }
+        in_arg = true;
+    }
+    if in_arg {
+        args.push(arg);
+    }
+    Ok(args)
+}
+
+/// Join args into a command line that [`shell_split`] gives back.
+///
+/// Args with only safe characters are kept as is, others are single
+/// quoted.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-storage")))]
+pub(crate) fn join(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            let safe = |c: char| c.is_ascii_alphanumeric() || "@%+=:,./_-".contains(c);
+            if !arg.is_empty() && arg.chars().all(safe) {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_quotes() {
+        assert_eq!(
+            shell_split(r#"  foo --bar "two words" 'it''s' a\ b "q\"\n" '' "#).unwrap(),
+            ["foo", "--bar", "two words", "its", "a b", "q\"\\n", ""]
+        );
+        assert_eq!(shell_split("").unwrap(), Vec::<String>::new());
+        assert_eq!(shell_split("'open"), Err(SplitError::UnbalancedSingleQuote));
+        assert_eq!(
+            shell_split("\"open"),
+            Err(SplitError::UnbalancedDoubleQuote)
+        );
+        assert_eq!(shell_split("end\\"), Err(SplitError::TrailingBackslash));
+    }
+
+    #[test]
+    fn join_round_trip() {
+        let args: Vec<String> = ["--name=bob", "two words", "it's", "", "$HOME", "-v"]
+            .map(String::from)
+            .into();
+        let line = join(&args);
+        assert_eq!(line, r"--name=bob 'two words' 'it'\''s' '' '$HOME' -v");
+        assert_eq!(shell_split(&line).unwrap(), args);
+    }
+}
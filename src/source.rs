@@ -0,0 +1,1031 @@
+//! # Where on the web the args are read from
+//!
+//! The query string by default.  Single page apps often keep their state
+//! in the `#` fragment instead, which the server never sees, and a
+//! returning user's args can be kept in `localStorage`.
+//!
+//! Wasm without a browser, under WASI or Node, has no page and no url, so
+//! the real command line is read instead.  A Web Worker has no `window`
+//! either, but has the url of its script.
+
+#[cfg(all(target_arch = "wasm32", feature = "web-storage"))]
+use crate::shell;
+#[cfg(any(test, target_arch = "wasm32"))]
+use crate::WeError;
+use cliw::url_args::UrlArgs;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+/// Makes a future of url args, set with [`set_async_arg_source`].
+type AsyncSource = Rc<dyn Fn() -> Pin<Box<dyn Future<Output = Vec<String>>>>>;
+
+thread_local! {
+    static SOURCE: RefCell<ArgSource> = const { RefCell::new(ArgSource::Query) };
+    static PROGRAM_NAME: RefCell<Option<ProgramName>> = const { RefCell::new(None) };
+    static ASYNC_SOURCE: RefCell<Option<AsyncSource>> = const { RefCell::new(None) };
+    static PAGE_URL: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// # Where the args are on the web
+///
+/// Set with [`set_arg_source`].  Only the webpage is affected, not urls
+/// given to functions like [`WebArgs::from_url`](crate::WebArgs::from_url).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ArgSource {
+    /// The query string, `page.html?verbose&n=1`.  The default.
+    #[default]
+    Query,
+    /// The fragment, `page.html#verbose&n=1`, parsed as a query string.
+    /// A fragment with a `?`, like the `#/route?verbose` of a hash router,
+    /// gives the part after the `?`.  Needs the "web-hash" feature, without
+    /// it the query string is read.
+    Hash,
+    /// The query string and then the fragment, `page.html?level=1#level=2`.
+    /// The fragment args come last, so they win where clap keeps the last
+    /// value, `level` is `2` with
+    /// [`Command::args_override_self`](clap::Command::args_override_self).
+    /// Without it clap reports the repeated arg.  Args that collect every
+    /// value get both.
+    /// Needs the "web-hash" feature, like [`Hash`](ArgSource::Hash).
+    Merged,
+    /// A command line saved in the [`localStorage`] item with the key, by
+    /// [`save_args_to_local_storage`] for example.  It is split like a
+    /// shell splits it, `--name "two words"` is two args, and given to clap
+    /// as is, not translated like a query.  The program name is set with
+    /// [`set_program_name`], like for a query.  No item gives no args.  A
+    /// command line with an unbalanced quote is an error.  Needs the
+    /// "web-storage" feature, without it the query string is read.
+    ///
+    /// [`localStorage`]: https://developer.mozilla.org/en-US/docs/Web/API/Window/localStorage
+    LocalStorage {
+        /// The `localStorage` key.
+        key: String,
+    },
+    /// A whole command line in the query parameter with this name,
+    /// `?args=foo%20--bar%20%22two%20words%22` with `"args"`.  The value is
+    /// decoded, a `+` is a space like in a form submission and `%2B` is a
+    /// plus, then split like a shell splits it with [`shell_split`](crate::shell_split).
+    /// The args are given to clap untranslated, with control characters
+    /// stripped or rejected as set in
+    /// [`Config::control_chars`](crate::Config::control_chars).  No
+    /// parameter gives no args.  A command line with an unbalanced quote is reported like a clap error.  Needs the
+    /// "web-single-param" feature, without it the query string is read.
+    SingleParam(String),
+    /// The first message of args posted by the parent frame of an
+    /// `<iframe>`, `iframe.contentWindow.postMessage("verbose&n=1", "*")`.
+    /// The message data is a query string, or an array of query strings
+    /// like `["verbose", "n=1"]`, translated like the url's.  Messages from
+    /// elsewhere, or with other data, are ignored.  Without a message
+    /// within `timeout_ms` the query string is read.
+    ///
+    /// Only [`we_parse_async`](crate::WeParser::we_parse_async()) waits for
+    /// the message, the other parse functions read the query string.
+    /// Needs the "web-post-message" feature, without it the query string
+    /// is read.
+    PostMessage {
+        /// How long to wait for the message, in milliseconds.
+        timeout_ms: u32,
+    },
+    /// A JSON object in the query parameter with this name,
+    /// `?json=%7B%22name%22%3A%22bob%22%2C%22verbose%22%3Atrue%7D` with
+    /// `"json"`.  Each key is an option, `{"name": "bob"}` gives
+    /// `--name=bob`, `true` gives the bare flag and `false` or `null`
+    /// nothing, an array gives the option once for each value.  Only JSON
+    /// booleans are flags, the string `"true"` is a value, `--name=true`.
+    /// Nested objects are not supported.  The args are given to clap
+    /// untranslated, with control characters stripped or rejected as set in
+    /// [`Config::control_chars`](crate::Config::control_chars).  No
+    /// parameter gives no args.  A
+    /// value that doesn't parse is reported like a clap error.  Needs the
+    /// "json-args" feature, without it the query string is read.
+    Json(String),
+    /// The url path after `base` as positional args, then the query string,
+    /// `/mytool/convert/input.txt?verbose` with `base` `"/mytool"` gives
+    /// `convert input.txt --verbose`.  Each segment is percent-decoded, so
+    /// `%20` is a space, and empty segments, like that of a trailing slash,
+    /// are skipped.  A segment naming a subcommand selects it, like a
+    /// positional on the command line.  With
+    /// [`Config::strict_path_subcommands`](crate::Config::strict_path_subcommands)
+    /// a first segment that isn't a subcommand is an error.  A path outside
+    /// `base` gives no positionals.  Needs the "web-path" feature, without
+    /// it only the query string is read.
+    Path {
+        /// The path prefix of the app, like `"/mytool"`.
+        base: String,
+    },
+}
+
+/// # Set where the args are read from on the web
+///
+/// Applies to every later parse on the current thread.  Not used on
+/// native.
+///
+/// # Example
+/// ``` rust
+/// we_clap::set_arg_source(we_clap::ArgSource::Hash);
+/// ```
+pub fn set_arg_source(source: ArgSource) {
+    SOURCE.with(|current| *current.borrow_mut() = source);
+}
+
+/// The arg source of this thread.
+pub(crate) fn arg_source() -> ArgSource {
+    SOURCE.with(|source| source.borrow().clone())
+}
+
+/// # Set an async source of args on the web
+///
+/// [`we_parse_async`](crate::WeParser::we_parse_async()) calls `source` and
+/// awaits the args it resolves to, in place of the [`ArgSource`], for args
+/// that arrive after load like a fetched remote config.  The args are like
+/// those of a url, the url first and then each decoded query string, and
+/// are translated like the url's, see [`WeArgsSource`].
+///
+/// Applies to every later async parse on the current thread, the other
+/// parse functions don't wait.  Not used on native.
+///
+/// # Example
+/// ``` rust
+/// we_clap::set_async_arg_source(|| async {
+///     // fetch the config here
+///     vec!["/".to_string(), "verbose".to_string()]
+/// });
+/// ```
+pub fn set_async_arg_source<F, S>(source: F)
+where
+    F: Fn() -> S + 'static,
+    S: Future<Output = Vec<String>> + 'static,
+{
+    let source: AsyncSource = Rc::new(move || Box::pin(source()));
+    ASYNC_SOURCE.with(|current| *current.borrow_mut() = Some(source));
+}
+
+/// The args of the async source, or posted by the parent frame, for
+/// [`we_parse_async`](crate::WeParser::we_parse_async()).  `None` on native,
+/// and on the web with neither.
+pub(crate) async fn async_args() -> Option<Vec<String>> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let source = ASYNC_SOURCE.with(|source| source.borrow().clone());
+        match source {
+            Some(source) => Some(source().await),
+            #[cfg(feature = "web-post-message")]
+            None => crate::message::posted_args().await,
+            #[cfg(not(feature = "web-post-message"))]
+            None => None,
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        None
+    }
+}
+
+/// # The program name on the web
+///
+/// Set with [`set_program_name`].  Without one the name of the command is
+/// used, the crate name with `#[command(name)]` left out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProgramName {
+    /// This name.
+    Static(String),
+    /// The title of the page, `document.title`, so usage matches the page
+    /// name.  An empty title, or no document, gives the command name.
+    DocumentTitle,
+}
+
+impl From<&str> for ProgramName {
+    fn from(name: &str) -> Self {
+        Self::Static(name.to_string())
+    }
+}
+
+impl From<String> for ProgramName {
+    fn from(name: String) -> Self {
+        Self::Static(name)
+    }
+}
+
+/// # Set the program name on the web
+///
+/// The name given to clap as the first arg, argv\[0\], in place of the
+/// page url.  Clap shows it in usage and help, `Usage: mytool [OPTIONS]`.
+/// A name, or [`ProgramName::DocumentTitle`] for the page title.  Without
+/// it the name of the command is used.  Not used on native, where the
+/// program name comes from the command line.
+///
+/// # Example
+/// ``` rust
+/// we_clap::set_program_name("mytool");
+/// we_clap::set_program_name(we_clap::ProgramName::DocumentTitle);
+/// ```
+pub fn set_program_name(name: impl Into<ProgramName>) {
+    let name = name.into();
+    PROGRAM_NAME.with(|current| *current.borrow_mut() = Some(name));
+}
+
+/// The program name set for the web, if any.
+pub(crate) fn program_name() -> Option<String> {
+    let name = PROGRAM_NAME.with(|name| name.borrow().clone())?;
+    resolve_name(&name, document_title)
+}
+
+/// How long to wait for a posted message, with
+/// [`ArgSource::PostMessage`].
+#[cfg(all(target_arch = "wasm32", feature = "web-post-message"))]
+pub(crate) fn post_message_timeout() -> Option<u32> {
+    SOURCE.with(|source| match *source.borrow() {
+        ArgSource::PostMessage { timeout_ms } => Some(timeout_ms),
+        _ => None,
+    })
+}
+
+/// The name, with the title from `title` for the document title.  An
+/// empty title gives none.
+fn resolve_name(name: &ProgramName, title: impl FnOnce() -> Option<String>) -> Option<String> {
+    match name {
+        ProgramName::Static(name) => Some(name.clone()),
+        ProgramName::DocumentTitle => title()
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty()),
+    }
+}
+
+/// The title of the page, none on native.
+fn document_title() -> Option<String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        web_sys::window()?
+            .document()
+            .map(|document| document.title())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        None
+    }
+}
+
+/// # Save args to `localStorage`
+///
+/// Joins the args into one command line, quoting where needed, and stores
+/// it in the `localStorage` item with the key, for
+/// [`ArgSource::LocalStorage`] to read on the next visit.  Give the args
+/// after the program name, `&we_args()[1..]` for example.
+///
+/// Returns `true` if the args were saved.  Always `false` on native and
+/// without the "web-storage" feature.
+///
+/// # Example
+/// ``` rust
+/// let args = we_clap::we_args();
+/// let saved = we_clap::save_args_to_local_storage("my-app-args", &args[1.min(args.len())..]);
+/// ```
+pub fn save_args_to_local_storage(key: &str, args: &[String]) -> bool {
+    #[cfg(all(target_arch = "wasm32", feature = "web-storage"))]
+    {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .is_some_and(|storage| storage.set_item(key, &shell::join(args)).is_ok())
+    }
+    #[cfg(not(all(target_arch = "wasm32", feature = "web-storage")))]
+    {
+        let _ = (key, args);
+        false
+    }
+}
+
+/// # A source of url args
+///
+/// Where [`WeParser::we_parse_with_source()`](crate::WeParser::we_parse_with_source())
+/// and [`WeCommand::we_get_matches_with_source()`](crate::WeCommand::we_get_matches_with_source())
+/// get their args, instead of the webpage.  The args are like those of a
+/// url, the url first and then each decoded query string, and are
+/// translated like the url's.  The first arg stands in for the program
+/// name, so any string will do.  A canned list makes a host-side test, a
+/// cookie or `postMessage` source can be swapped in on the web.
+///
+/// Implemented for [`UrlArgs`] and for lists of strings.
+///
+/// # Example
+/// ``` rust
+/// use we_clap::WeArgsSource;
+///
+/// struct Cookie(String);
+///
+/// impl WeArgsSource for Cookie {
+///     fn args(&self) -> Vec<String> {
+///         let mut args = vec!["/".to_string()];
+///         args.extend(self.0.split('&').map(String::from));
+///         args
+///     }
+/// }
+///
+/// let cookie = Cookie("verbose&level=3".into());
+/// assert_eq!(cookie.args(), ["/", "verbose", "level=3"]);
+/// ```
+pub trait WeArgsSource {
+    /// The url args, the url first, then the decoded query strings.
+    fn args(&self) -> Vec<String>;
+}
+
+impl WeArgsSource for UrlArgs {
+    fn args(&self) -> Vec<String> {
+        self.clone()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+}
+
+impl<S: AsRef<str>> WeArgsSource for [S] {
+    fn args(&self) -> Vec<String> {
+        self.iter().map(|arg| arg.as_ref().to_string()).collect()
+    }
+}
+
+impl<S: AsRef<str>> WeArgsSource for Vec<S> {
+    fn args(&self) -> Vec<String> {
+        self.as_slice().args()
+    }
+}
+
+/// The web args, before translation.
+#[cfg(target_arch = "wasm32")]
+pub(crate) enum WebSource {
+    /// A url, its query strings to translate.
+    Url(UrlArgs),
+    /// A saved command line, split into args for clap.
+    Args(Vec<String>),
+}
+
+/// The web args from the [`ArgSource`].
+///
+/// Fails on a saved command line that doesn't split.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn web_source() -> Result<WebSource, WeError> {
+    if host() == Host::Headless {
+        return Ok(WebSource::Args(headless_args(
+            std::env::args_os()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+            node_argv,
+        )));
+    }
+    #[cfg(feature = "web-storage")]
+    if let ArgSource::LocalStorage { key } = SOURCE.with(|source| source.borrow().clone()) {
+        let mut args: Vec<String> = from_url(&page_url())
+            .next()
+            .map(|path| path.to_string_lossy().into_owned())
+            .into_iter()
+            .collect();
+        let saved = web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .and_then(|storage| storage.get_item(&key).ok().flatten());
+        if let Some(saved) = saved {
+            args.extend(crate::shell_split(&saved)?);
+        }
+        return Ok(WebSource::Args(args));
+    }
+    #[cfg(feature = "web-single-param")]
+    if let ArgSource::SingleParam(name) = SOURCE.with(|source| source.borrow().clone()) {
+        return single_param_args(&page_url(), &name).map(WebSource::Args);
+    }
+    #[cfg(feature = "json-args")]
+    if let ArgSource::Json(name) = SOURCE.with(|source| source.borrow().clone()) {
+        return json_param_args(&page_url(), &name).map(WebSource::Args);
+    }
+    Ok(WebSource::Url(url_args()))
+}
+
+/// What the wasm runs in.
+#[cfg(any(test, target_arch = "wasm32"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Host {
+    /// A page, with a `window`.
+    Page,
+    /// A Web Worker, with the `self.location` of its script.
+    Worker,
+    /// No browser, WASI or Node.
+    Headless,
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+impl Host {
+    /// WASI is never a browser, even if a `window` were somehow found.
+    fn detect(wasi: bool, window: bool, worker: bool) -> Self {
+        match (wasi, window, worker) {
+            (true, _, _) => Self::Headless,
+            (false, true, _) => Self::Page,
+            (false, false, true) => Self::Worker,
+            (false, false, false) => Self::Headless,
+        }
+    }
+}
+
+/// What the wasm runs in now.  Workers are only detected with the
+/// "web-worker" feature.
+#[cfg(target_arch = "wasm32")]
+fn host() -> Host {
+    if cfg!(target_os = "wasi") {
+        return Host::Headless;
+    }
+    Host::detect(false, web_sys::window().is_some(), worker_url().is_some())
+}
+
+/// The `self.location` of a Web Worker, `None` on a page or without the
+/// "web-worker" feature.
+#[cfg(target_arch = "wasm32")]
+fn worker_url() -> Option<String> {
+    #[cfg(feature = "web-worker")]
+    {
+        use wasm_bindgen::JsCast;
+        js_sys::global()
+            .dyn_into::<web_sys::WorkerGlobalScope>()
+            .ok()
+            .map(|worker| worker.location().href())
+    }
+    #[cfg(not(feature = "web-worker"))]
+    None
+}
+
+/// The command line of wasm without a browser.
+///
+/// The args from the environment, as under WASI, unless there are none.
+/// Then Node's `process.argv`, without the node binary, from `node_argv`.
+/// Then no args but the program name.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn headless_args(env_args: Vec<String>, node_argv: impl FnOnce() -> Vec<String>) -> Vec<String> {
+    if !env_args.is_empty() {
+        return env_args;
+    }
+    let mut argv = node_argv();
+    if argv.is_empty() {
+        vec![String::new()]
+    } else {
+        argv.remove(0);
+        argv
+    }
+}
+
+/// Node's `process.argv`, empty without Node or the "wasm-node" feature.
+#[cfg(target_arch = "wasm32")]
+fn node_argv() -> Vec<String> {
+    #[cfg(feature = "wasm-node")]
+    {
+        let global = js_sys::global();
+        let argv = js_sys::Reflect::get(&global, &"process".into())
+            .and_then(|process| js_sys::Reflect::get(&process, &"argv".into()));
+        if let Ok(argv) = argv {
+            if js_sys::Array::is_array(&argv) {
+                return js_sys::Array::from(&argv)
+                    .iter()
+                    .filter_map(|arg| arg.as_string())
+                    .collect();
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// The program name and the split command line in the query parameter.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-single-param")))]
+fn single_param_args(href: &str, name: &str) -> Result<Vec<String>, WeError> {
+    let mut args = page_path(href);
+    if let Some(value) = query_param(href, name) {
+        args.extend(crate::shell_split(&value)?);
+    }
+    Ok(args)
+}
+
+/// The program name and the args of the JSON object in the query
+/// parameter.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "json-args")))]
+fn json_param_args(href: &str, name: &str) -> Result<Vec<String>, WeError> {
+    use crate::config_file::Value;
+
+    let mut args = page_path(href);
+    if let Some(value) = query_param(href, name) {
+        let entries = crate::config_file::parse_json(&value).map_err(WeError::Json)?;
+        for (key, values) in entries {
+            let flag = crate::query::flag(&key);
+            match values.as_slice() {
+                [Value::Bool(true)] => args.push(flag),
+                [Value::Bool(false)] => {}
+                values => args.extend(values.iter().map(|value| format!("{flag}={value}"))),
+            }
+        }
+    }
+    Ok(args)
+}
+
+/// The page url, as the program name, in a list for the args.
+#[cfg(any(
+    test,
+    all(
+        target_arch = "wasm32",
+        any(feature = "web-single-param", feature = "json-args")
+    )
+))]
+fn page_path(href: &str) -> Vec<String> {
+    UrlArgs::from(href)
+        .next()
+        .map(|path| path.to_string_lossy().into_owned())
+        .into_iter()
+        .collect()
+}
+
+/// The decoded value of the query parameter, a `+` is a space.
+#[cfg(any(
+    test,
+    all(
+        target_arch = "wasm32",
+        any(feature = "web-single-param", feature = "json-args")
+    )
+))]
+fn query_param(href: &str, name: &str) -> Option<String> {
+    let base = href.split_once('#').map_or(href, |(base, _)| base);
+    let query = base.split_once('?').map_or("", |(_, query)| query);
+    let decode = |text: &str| {
+        percent_encoding::percent_decode_str(&text.replace('+', " "))
+            .decode_utf8_lossy()
+            .into_owned()
+    };
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (decode(key) == name).then(|| decode(value))
+    })
+}
+
+/// The args of the webpage url, from the [`ArgSource`].
+pub(crate) fn url_args() -> UrlArgs {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let href = page_url();
+        #[cfg(feature = "web-hash")]
+        {
+            let source = SOURCE.with(|source| source.borrow().clone());
+            if matches!(source, ArgSource::Hash | ArgSource::Merged) {
+                return from_url(&source_url(&source, &href));
+            }
+        }
+        #[cfg(feature = "web-path")]
+        if let ArgSource::Path { base } = SOURCE.with(|source| source.borrow().clone()) {
+            return from_url(&path_url(&href, &base, &crate::config().positional_key));
+        }
+        from_url(&href)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        UrlArgs::new()
+    }
+}
+
+/// # Read the page url again
+///
+/// The url of the webpage is read once, by the first web parse, and kept,
+/// so every later parse and [`we_args`](crate::we_args) sees the same args
+/// even if a router changes the url in between.  Call this after a
+/// navigation to have the next parse read the new url.
+///
+/// Only the url is kept, not the args, each parse still decodes its query
+/// strings.  Decoding is cheap next to reading the url from the browser.
+///
+/// [`subscribe_to_args`](crate::subscribe_to_args) calls it itself before
+/// each parse of a changed url, so parses after a `hashchange` or
+/// `popstate` see the new args.  Does nothing on native.
+///
+/// # Example
+/// ``` rust
+/// // after history.pushState(...)
+/// we_clap::refresh_args();
+/// ```
+pub fn refresh_args() {
+    PAGE_URL.with(|url| url.borrow_mut().take());
+}
+
+/// The url kept by the first read, or read now.  The url, not its args.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn cached_url(read: impl FnOnce() -> String) -> String {
+    PAGE_URL.with(|url| url.borrow_mut().get_or_insert_with(read).clone())
+}
+
+/// The url of the webpage, or the script of a Web Worker, read once until
+/// [`refresh_args`].
+#[cfg(target_arch = "wasm32")]
+fn page_url() -> String {
+    cached_url(|| match web_sys::window() {
+        Some(window) => window.location().href().unwrap_or_default(),
+        None => worker_url().unwrap_or_default(),
+    })
+}
+
+/// The args of a url, with a `+` in the query string decoded as a space.
+pub(crate) fn from_url(url: &str) -> UrlArgs {
+    UrlArgs::from(plus_as_space(url))
+}
+
+/// Encode each `+` of the query string as `%20`, like
+/// `application/x-www-form-urlencoded` reads it.  The path and fragment
+/// keep theirs, and an encoded `%2B` stays a plus.
+fn plus_as_space(url: &str) -> String {
+    let Some((path, rest)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let (query, fragment) = rest
+        .split_once('#')
+        .map_or((rest, None), |(query, fragment)| (query, Some(fragment)));
+    let mut url = format!("{path}?{}", query.replace('+', "%20"));
+    if let Some(fragment) = fragment {
+        url.push('#');
+        url.push_str(fragment);
+    }
+    url
+}
+
+/// The url with the path segments after `base` put first in its query
+/// string as positionals, `key=segment` for the positional key.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-path")))]
+fn path_url(href: &str, base: &str, key: &str) -> String {
+    let href = href.split_once('#').map_or(href, |(href, _)| href);
+    let (front, query) = href.split_once('?').unwrap_or((href, ""));
+    let queries: Vec<String> = path_segments(front, base)
+        .map(|segment| format!("{key}={}", segment.replace('+', "%2B")))
+        .chain((!query.is_empty()).then(|| query.to_string()))
+        .collect();
+    if queries.is_empty() {
+        front.to_string()
+    } else {
+        format!("{front}?{}", queries.join("&"))
+    }
+}
+
+/// The segments of the url path after `base`, still percent-encoded.
+/// Empty segments are skipped, and a path outside `base` has none.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-path")))]
+fn path_segments<'a>(href: &'a str, base: &str) -> impl Iterator<Item = &'a str> {
+    let href = href.split(['?', '#']).next().unwrap_or_default();
+    let path = match href.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("", |at| &rest[at..]),
+        None => href,
+    };
+    let rest = path
+        .strip_prefix(base.trim_end_matches('/'))
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+        .unwrap_or("");
+    rest.split('/').filter(|segment| !segment.is_empty())
+}
+
+/// With [`Config::strict_path_subcommands`](crate::Config::strict_path_subcommands)
+/// and [`ArgSource::Path`], fail on a first path segment that isn't a
+/// subcommand.
+#[cfg(all(target_arch = "wasm32", feature = "web-path"))]
+pub(crate) fn check_path_subcommand(command: &clap::Command) -> Result<(), WeError> {
+    match SOURCE.with(|source| source.borrow().clone()) {
+        ArgSource::Path { base } if crate::config().strict_path_subcommands => {
+            path_subcommand(command, &page_url(), &base)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fail if the first path segment after `base` isn't a subcommand.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-path")))]
+fn path_subcommand(command: &clap::Command, href: &str, base: &str) -> Result<(), WeError> {
+    let Some(first) = path_segments(href, base).next() else {
+        return Ok(());
+    };
+    let name = percent_encoding::percent_decode_str(first).decode_utf8_lossy();
+    match command.find_subcommand(name.as_ref()) {
+        Some(_) => Ok(()),
+        None => Err(WeError::UnknownSubcommand(name.into_owned())),
+    }
+}
+
+/// The url with the args of the source as its query string.
+#[cfg(any(test, all(target_arch = "wasm32", feature = "web-hash")))]
+fn source_url(source: &ArgSource, href: &str) -> String {
+    let (base, fragment) = href.split_once('#').unwrap_or((href, ""));
+    let (path, query) = base.split_once('?').unwrap_or((base, ""));
+    let hash = fragment
+        .split_once('?')
+        .map_or(fragment, |(_, query)| query);
+    let queries: Vec<&str> = match source {
+        ArgSource::Query
+        | ArgSource::LocalStorage { .. }
+        | ArgSource::SingleParam(_)
+        | ArgSource::PostMessage { .. }
+        | ArgSource::Json(_)
+        | ArgSource::Path { .. } => vec![query],
+        ArgSource::Hash => vec![hash],
+        ArgSource::Merged => vec![query, hash],
+    };
+    let queries: Vec<&str> = queries.into_iter().filter(|q| !q.is_empty()).collect();
+    if queries.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{}", queries.join("&"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source_args(source: ArgSource, href: &str) -> Vec<String> {
+        from_url(&source_url(&source, href))
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn fragment_as_query() {
+        let hash_args = |href| source_args(ArgSource::Hash, href);
+        assert_eq!(
+            hash_args("https://example.org/app.html?ignored=1#verbose&n=a%20b"),
+            ["https://example.org/app.html", "verbose", "n=a b"]
+        );
+        assert_eq!(
+            hash_args("https://example.org/#/settings?theme=dark"),
+            ["https://example.org/", "theme=dark"]
+        );
+        assert_eq!(
+            hash_args("https://example.org/app.html?n=1"),
+            ["https://example.org/app.html"]
+        );
+
+        assert_eq!(
+            source_args(ArgSource::Query, "/app.html?n=1#m=2"),
+            ["/app.html", "n=1"]
+        );
+
+        set_arg_source(ArgSource::Hash);
+        assert_eq!(
+            SOURCE.with(|source| source.borrow().clone()),
+            ArgSource::Hash
+        );
+        set_arg_source(ArgSource::default());
+    }
+
+    #[test]
+    fn merged() {
+        use clap::{value_parser, Arg, Command};
+
+        let href = "https://example.org/app.html?level=1&tag=a#level=2&tag=b";
+        let args = source_args(ArgSource::Merged, href);
+        assert_eq!(
+            args,
+            [
+                "https://example.org/app.html",
+                "level=1",
+                "tag=a",
+                "level=2",
+                "tag=b"
+            ]
+        );
+
+        let command = Command::new("app")
+            .args_override_self(true)
+            .arg(
+                Arg::new("level")
+                    .long("level")
+                    .value_parser(value_parser!(u8)),
+            )
+            .arg(Arg::new("tag").long("tag").action(clap::ArgAction::Append));
+        let args = crate::translate::url_to_args(
+            &command,
+            UrlArgs::from(source_url(&ArgSource::Merged, href)),
+        )
+        .unwrap();
+        let matches = command.try_get_matches_from(args).unwrap();
+        assert_eq!(matches.get_one::<u8>("level"), Some(&2));
+        let tags: Vec<&String> = matches.get_many("tag").unwrap().collect();
+        assert_eq!(tags, ["a", "b"]);
+
+        assert_eq!(source_args(ArgSource::Merged, "/?n=1"), ["/", "n=1"]);
+        assert_eq!(source_args(ArgSource::Merged, "/#n=2"), ["/", "n=2"]);
+        assert_eq!(source_args(ArgSource::Merged, "/"), ["/"]);
+    }
+
+    #[test]
+    fn program_name_in_usage() {
+        use clap::{error::ErrorKind, Command};
+
+        let usage = || {
+            let command = Command::new("app");
+            let args = crate::translate::url_to_args(&command, from_url("/page.html?--help"));
+            let err = command.try_get_matches_from(args.unwrap()).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::DisplayHelp);
+            err.to_string()
+        };
+
+        assert!(usage().contains("Usage: app"), "{}", usage());
+        set_program_name("mytool");
+        assert!(usage().contains("Usage: mytool"), "{}", usage());
+        PROGRAM_NAME.with(|name| *name.borrow_mut() = None);
+    }
+
+    #[test]
+    fn args_sources() {
+        let url = from_url("https://example.org/app?v&name=Jo+Jo");
+        assert_eq!(url.args(), ["https://example.org/app", "v", "name=Jo Jo"]);
+        assert_eq!(vec!["/", "-v"].args(), ["/", "-v"]);
+        assert_eq!(["/".to_string()][..].args(), ["/"]);
+    }
+
+    #[test]
+    fn async_source() {
+        set_async_arg_source(|| async { vec!["/".to_string(), "v".to_string()] });
+        let source = ASYNC_SOURCE.with(|source| source.borrow().clone()).unwrap();
+        assert_eq!(crate::future::block_on(source()), ["/", "v"]);
+        assert_eq!(crate::future::block_on(async_args()), None);
+    }
+
+    #[test]
+    fn url_cache() {
+        assert_eq!(cached_url(|| "/?n=1".into()), "/?n=1");
+        assert_eq!(cached_url(|| unreachable!()), "/?n=1");
+        refresh_args();
+        assert_eq!(cached_url(|| "/?n=2".into()), "/?n=2");
+        refresh_args();
+    }
+
+    #[test]
+    fn program_names() {
+        let title = |text: &str| {
+            let text = text.to_string();
+            move || Some(text)
+        };
+        let page = ProgramName::DocumentTitle;
+        assert_eq!(
+            resolve_name(&page, title(" Tool ")).as_deref(),
+            Some("Tool")
+        );
+        assert_eq!(resolve_name(&page, title("")), None);
+        assert_eq!(resolve_name(&page, || None), None);
+        assert_eq!(
+            resolve_name(&"tool".into(), || unreachable!()).as_deref(),
+            Some("tool")
+        );
+    }
+
+    #[test]
+    fn path_segments() {
+        let args = |href: &str| {
+            let url = path_url(href, "/mytool", "_");
+            crate::translate::tests::url_args(&url)[1..].to_vec()
+        };
+        assert_eq!(
+            args("https://example.org/mytool/convert/input%20file.txt?level=2#top"),
+            ["convert", "input file.txt", "--level=2"]
+        );
+        assert_eq!(args("https://example.org/mytool"), Vec::<String>::new());
+        assert_eq!(args("https://example.org/mytool/"), Vec::<String>::new());
+        assert_eq!(args("/mytool/convert/"), ["convert"]);
+        assert_eq!(args("/mytool/a+b/c%2Fd"), ["a+b", "c/d"]);
+        assert_eq!(args("/mytoolbox/convert?n=1"), ["-n=1"]);
+    }
+
+    #[test]
+    fn strict_path() {
+        let command = clap::Command::new("mytool")
+            .subcommand(clap::Command::new("convert").alias("conv"))
+            .subcommand(clap::Command::new("two words"));
+        let check = |href: &str| path_subcommand(&command, href, "/mytool/");
+        assert!(check("https://example.org/mytool/convert/input.txt?v").is_ok());
+        assert!(check("/mytool/conv").is_ok());
+        assert!(check("/mytool/two%20words/").is_ok());
+        assert!(check("/mytool/").is_ok());
+        assert!(check("/elsewhere/frobnicate").is_ok());
+        let err = check("https://example.org/mytool/frobnicate/input.txt").unwrap_err();
+        assert!(matches!(&err, WeError::UnknownSubcommand(name) if name == "frobnicate"));
+        let err = err.into_clap(&command);
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidSubcommand);
+        assert!(err.to_string().contains("'frobnicate' in the url path"));
+        assert!(!crate::Config::default().strict_path_subcommands);
+    }
+
+    #[test]
+    fn hosts() {
+        assert_eq!(Host::detect(false, true, false), Host::Page);
+        assert_eq!(Host::detect(false, false, true), Host::Worker);
+        assert_eq!(Host::detect(false, false, false), Host::Headless);
+        assert_eq!(Host::detect(true, true, false), Host::Headless);
+    }
+
+    #[test]
+    fn headless() {
+        let wasi = headless_args(vec!["app.wasm".into(), "-v".into()], Vec::new);
+        assert_eq!(wasi, ["app.wasm", "-v"]);
+
+        let node = headless_args(Vec::new(), || {
+            ["/usr/bin/node", "app.js", "--name", "bob"]
+                .map(String::from)
+                .into()
+        });
+        assert_eq!(node, ["app.js", "--name", "bob"]);
+
+        assert_eq!(headless_args(Vec::new(), Vec::new), [""]);
+    }
+
+    #[test]
+    fn plus_is_space() {
+        let args = |url| -> Vec<String> {
+            from_url(url)
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(
+            args("/a+b.html?name=John+Doe&full=John%20Doe&sum=1%2B1#x+y"),
+            ["/a+b.html", "name=John Doe", "full=John Doe", "sum=1+1"]
+        );
+        assert_eq!(plus_as_space("/a+b.html"), "/a+b.html");
+        assert_eq!(plus_as_space("/?a+b#c+d"), "/?a%20b#c+d");
+        assert_eq!(
+            source_args(ArgSource::Hash, "/?q=1#name=John+Doe"),
+            ["/", "name=John Doe"]
+        );
+    }
+
+    #[test]
+    fn json_param() {
+        let href = "/app.html?json=%7B%22name%22%3A%22bob%22%2C%22verbose%22%3Atrue%2C%22quiet%22%3Afalse%2C%22tag%22%3A%5B%22a%22%2C%22b%22%5D%7D";
+        assert_eq!(
+            json_param_args(href, "json").unwrap(),
+            ["/app.html", "--name=bob", "--verbose", "--tag=a", "--tag=b"]
+        );
+        assert_eq!(json_param_args("/app.html", "json").unwrap(), ["/app.html"]);
+        let href = "/app.html?json=%7B%22name%22%3A%22true%22%2C%22mode%22%3A%22false%22%7D";
+        assert_eq!(
+            json_param_args(href, "json").unwrap(),
+            ["/app.html", "--name=true", "--mode=false"]
+        );
+        let args = json_param_args("/?json=%7B%22name%22%3A%22b%5Cu0000b%22%7D", "json").unwrap();
+        assert_eq!(args, ["/", "--name=b\0b"]);
+        let command = clap::Command::new("app");
+        let args = crate::translate::given_args(&command, args).unwrap();
+        assert_eq!(args, ["app", "--name=bb"]);
+        let err = json_param_args("/?json=%7B%22name%22", "json").unwrap_err();
+        assert!(matches!(err, WeError::Json(_)));
+    }
+
+    #[test]
+    fn single_param() {
+        use crate::SplitError;
+
+        let href = "https://example.org/app.html?theme=dark&args=foo%20--bar+%22two%20words%22+a%2Bb#args=no";
+        assert_eq!(
+            single_param_args(href, "args").unwrap(),
+            [
+                "https://example.org/app.html",
+                "foo",
+                "--bar",
+                "two words",
+                "a+b"
+            ]
+        );
+        assert_eq!(
+            single_param_args("/app.html?theme=dark", "args").unwrap(),
+            ["/app.html"]
+        );
+
+        let err = single_param_args("/?args=%22open", "args").unwrap_err();
+        assert!(matches!(
+            err,
+            WeError::Split(SplitError::UnbalancedDoubleQuote)
+        ));
+        let err = err.into_clap(&clap::Command::new("app"));
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+        assert!(err.to_string().contains("unbalanced double quote"));
+    }
+
+    #[test]
+    fn single_param_control_chars() {
+        use crate::translate::given_args;
+        use crate::{set_config, Config, ControlChars};
+
+        let command = clap::Command::new("app");
+        let args = single_param_args("/app.html?args=--name%20b%00b", "args").unwrap();
+        assert_eq!(
+            given_args(&command, args.clone()).unwrap(),
+            ["app", "--name", "bb"]
+        );
+        set_config(Config {
+            control_chars: ControlChars::Reject,
+            ..Config::default()
+        });
+        let err = given_args(&command, args).unwrap_err();
+        assert!(matches!(err, WeError::InvalidCharacter(arg) if arg == "b\\0b"));
+        set_config(Config::default());
+    }
+
+    #[test]
+    fn local_storage() {
+        let source = ArgSource::LocalStorage { key: "args".into() };
+        assert_eq!(
+            source_args(source, "/app.html?n=1#m=2"),
+            ["/app.html", "n=1"]
+        );
+        assert!(!save_args_to_local_storage("args", &["-v".into()]));
+    }
+}
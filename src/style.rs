@@ -0,0 +1,334 @@
+//! # Colored output in the browser console
+//!
+//! Clap colors help and errors with ANSI escape codes, which a browser
+//! console shows as garbage.  Browser consoles style text with `%c` in the
+//! message instead, each `%c` taking the CSS of the next argument:
+//!
+//! ``` js
+//! console.log("%cUsage:%c app [OPTIONS]", "font-weight: bold", "");
+//! ```
+//!
+//! With the "web-console-styled" feature web output to the console is
+//! translated this way, so headings, usage and errors are colored in
+//! devtools.  Without it the console gets plain text.
+//!
+//! A `?color=never` in the url turns the colors off, and `?color=always`
+//! keeps them for the console even without the feature, as ANSI codes that
+//! devtools show.  `?color=auto` is the default.  Alerts are always plain.
+//! Apps with their own `--color` arg get it as usual instead.
+
+use clap::ColorChoice;
+#[cfg(any(test, target_arch = "wasm32"))]
+use clap::Command;
+use std::cell::Cell;
+
+thread_local! {
+    static COLOR: Cell<ColorChoice> = const { Cell::new(ColorChoice::Auto) };
+}
+
+/// Take the `--color` of the web args, unless the command has its own.
+///
+/// Gives the last valid choice.  Each `--color=when`, and each `--color`
+/// followed by a valid choice, is removed from the args.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) fn take_color(command: &Command, args: &mut Vec<String>) -> Option<ColorChoice> {
+    if command
+        .get_arguments()
+        .any(|arg| arg.get_long() == Some("color"))
+    {
+        return None;
+    }
+    let mut choice = None;
+    let mut kept = Vec::with_capacity(args.len());
+    let mut rest = std::mem::take(args).into_iter().peekable();
+    while let Some(arg) = rest.next() {
+        if let Some(when) = arg.strip_prefix("--color=") {
+            choice = when.parse().ok().or(choice);
+            continue;
+        }
+        if arg == "--color" {
+            if let Some(when) = rest.peek().and_then(|when| when.parse().ok()) {
+                rest.next();
+                choice = Some(when);
+                continue;
+            }
+        }
+        kept.push(arg);
+    }
+    *args = kept;
+    choice
+}
+
+/// Use the color choice for web output.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn set_color(choice: ColorChoice) {
+    COLOR.set(choice);
+}
+
+/// Whether web console output keeps its colors, for the url's color
+/// choice and whether the "web-console-styled" feature is on.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) fn colored(styled_feature: bool) -> bool {
+    colored_for(COLOR.get(), styled_feature)
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+fn colored_for(choice: ColorChoice, styled_feature: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => styled_feature,
+    }
+}
+
+/// # Translate ANSI colored text for the browser console
+///
+/// Returns the format string and the CSS for each `%c` in it, ready to be
+/// the arguments of `console.log`.  SGR codes for bold, dim, italic,
+/// underline, and the 8, 16, 256 and RGB color foreground and background
+/// colors are translated.  Other escape sequences are dropped.  A `%` in
+/// the text is escaped as `%%`.
+///
+/// Text without any SGR codes is returned as is, with no CSS.
+///
+/// # Example
+/// ``` rust
+/// let (format, css) = we_clap::ansi_to_console_css("\x1b[1mUsage:\x1b[0m app");
+///
+/// assert_eq!(format, "%cUsage:%c app");
+/// assert_eq!(css, ["font-weight: bold", ""]);
+/// ```
+#[must_use]
+pub fn ansi_to_console_css(input: &str) -> (String, Vec<String>) {
+    let mut format = String::with_capacity(input.len());
+    let mut plain = String::with_capacity(input.len());
+    let mut css = Vec::new();
+    let mut style = Style::default();
+    let mut changed = false;
+
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // A control sequence is `ESC [`, parameters, then a final byte.
+            if chars.next() != Some('[') {
+                continue;
+            }
+            let mut params = String::new();
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    if c == 'm' {
+                        style.apply(&params);
+                        changed = true;
+                    }
+                    break;
+                }
+                params.push(c);
+            }
+            continue;
+        }
+        if changed {
+            format.push_str("%c");
+            css.push(style.css());
+            changed = false;
+        }
+        if c == '%' {
+            format.push('%');
+        }
+        format.push(c);
+        plain.push(c);
+    }
+
+    if css.is_empty() {
+        (plain, css)
+    } else {
+        (format, css)
+    }
+}
+
+/// The text style set by SGR codes so far.
+#[derive(Default)]
+struct Style {
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    fg: Option<String>,
+    bg: Option<String>,
+}
+
+impl Style {
+    /// Apply the `;` separated codes of one SGR sequence.
+    fn apply(&mut self, params: &str) {
+        let mut codes = params
+            .split(';')
+            .map(|code| code.parse::<u16>().unwrap_or(0));
+        while let Some(code) = codes.next() {
+            match code {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => (self.bold, self.dim) = (false, false),
+                23 => self.italic = false,
+                24 => self.underline = false,
+                30..=37 => self.fg = Some(palette(code - 30)),
+                90..=97 => self.fg = Some(palette(code - 90 + 8)),
+                38 => self.fg = extended(&mut codes),
+                39 => self.fg = None,
+                40..=47 => self.bg = Some(palette(code - 40)),
+                100..=107 => self.bg = Some(palette(code - 100 + 8)),
+                48 => self.bg = extended(&mut codes),
+                49 => self.bg = None,
+                _ => {}
+            }
+        }
+    }
+
+    /// The style as CSS, empty for the default style.
+    fn css(&self) -> String {
+        let mut rules = Vec::new();
+        if let Some(fg) = &self.fg {
+            rules.push(format!("color: {fg}"));
+        }
+        if let Some(bg) = &self.bg {
+            rules.push(format!("background: {bg}"));
+        }
+        if self.bold {
+            rules.push("font-weight: bold".to_string());
+        } else if self.dim {
+            rules.push("font-weight: lighter".to_string());
+        }
+        if self.italic {
+            rules.push("font-style: italic".to_string());
+        }
+        if self.underline {
+            rules.push("text-decoration: underline".to_string());
+        }
+        rules.join("; ")
+    }
+}
+
+/// A `5;n` 256 color or `2;r;g;b` RGB color after a 38 or 48 code.
+fn extended(codes: &mut impl Iterator<Item = u16>) -> Option<String> {
+    match codes.next()? {
+        5 => Some(palette(codes.next()?)),
+        2 => {
+            let (r, g, b) = (codes.next()?, codes.next()?, codes.next()?);
+            Some(format!("rgb({r}, {g}, {b})"))
+        }
+        _ => None,
+    }
+}
+
+/// A color of the xterm 256 color palette.
+fn palette(n: u16) -> String {
+    const BASIC: [&str; 16] = [
+        "#000000", "#cd0000", "#00cd00", "#cdcd00", "#0000ee", "#cd00cd", "#00cdcd", "#e5e5e5",
+        "#7f7f7f", "#ff0000", "#00ff00", "#ffff00", "#5c5cff", "#ff00ff", "#00ffff", "#ffffff",
+    ];
+    const LEVELS: [u16; 6] = [0, 95, 135, 175, 215, 255];
+    match n {
+        0..=15 => BASIC[usize::from(n)].to_string(),
+        16..=231 => {
+            let n = n - 16;
+            let (r, g, b) = (n / 36, n / 6 % 6, n % 6);
+            format!(
+                "rgb({}, {}, {})",
+                LEVELS[usize::from(r)],
+                LEVELS[usize::from(g)],
+                LEVELS[usize::from(b)]
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (n.min(255) - 232);
+            format!("rgb({level}, {level}, {level})")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+
+    #[test]
+    fn color_from_url() {
+        let command = Command::new("app").arg(Arg::new("name").long("name"));
+        let mut args: Vec<String> = ["app", "--color=never", "--name=x", "--color", "always"]
+            .map(String::from)
+            .into();
+        assert_eq!(take_color(&command, &mut args), Some(ColorChoice::Always));
+        assert_eq!(args, ["app", "--name=x"]);
+
+        let mut args: Vec<String> = ["app", "--color=rainbow"].map(String::from).into();
+        assert_eq!(take_color(&command, &mut args), None);
+        assert!(args.len() == 1);
+
+        let own = command.arg(Arg::new("color").long("color"));
+        let mut args: Vec<String> = ["app", "--color=never"].map(String::from).into();
+        assert_eq!(take_color(&own, &mut args), None);
+        assert_eq!(args, ["app", "--color=never"]);
+    }
+
+    #[test]
+    fn color_choice_renders() {
+        let help = Command::new("demo")
+            .arg(Arg::new("name").long("name"))
+            .render_help();
+        let render = |choice, styled_feature| {
+            if colored_for(choice, styled_feature) {
+                help.ansi().to_string()
+            } else {
+                help.to_string()
+            }
+        };
+
+        assert!(render(ColorChoice::Always, false).contains('\x1b'));
+        assert!(!render(ColorChoice::Never, true).contains('\x1b'));
+        assert!(render(ColorChoice::Auto, true).contains('\x1b'));
+        assert!(!render(ColorChoice::Auto, false).contains('\x1b'));
+    }
+
+    #[test]
+    fn sgr_to_css() {
+        let (format, css) = ansi_to_console_css("\x1b[1;4;31merror:\x1b[0m 100%");
+        assert_eq!(format, "%cerror:%c 100%%");
+        assert_eq!(
+            css,
+            [
+                "color: #cd0000; font-weight: bold; text-decoration: underline",
+                ""
+            ]
+        );
+
+        let (format, css) = ansi_to_console_css("\x1b[38;5;196;48;2;1;2;3mx\x1b[39;49;3my");
+        assert_eq!(format, "%cx%cy");
+        assert_eq!(
+            css,
+            [
+                "color: rgb(255, 0, 0); background: rgb(1, 2, 3)",
+                "font-style: italic"
+            ]
+        );
+
+        assert_eq!(ansi_to_console_css("100%"), ("100%".to_string(), vec![]));
+        assert_eq!(
+            ansi_to_console_css("\x1b[2Kcleared"),
+            ("cleared".to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn clap_help() {
+        let help = Command::new("demo")
+            .arg(Arg::new("name").long("name"))
+            .color(ColorChoice::Always)
+            .render_help();
+        let (format, css) = ansi_to_console_css(&help.ansi().to_string());
+
+        assert_eq!(format.replace("%c", ""), help.to_string());
+        assert_eq!(format.matches("%c").count(), css.len());
+        assert!(css.iter().any(|css| css.contains("bold")));
+    }
+}
@@ -0,0 +1,114 @@
+//! # Live args for single page apps
+//!
+//! A single page app changes its url without a page reload, by editing the
+//! query string, the `#` fragment, or with history navigation.  With the
+//! "web-subscribe" feature [`subscribe_to_args`] listens for `hashchange`
+//! and `popstate` and parses the args again on each change.
+
+use clap::{error, Parser};
+
+/// Events that mean the url changed without a reload.
+#[cfg(all(target_arch = "wasm32", feature = "web-subscribe"))]
+const EVENTS: [&str; 2] = ["hashchange", "popstate"];
+
+/// # A subscription to url changes
+///
+/// From [`subscribe_to_args`].  The listeners are removed when it is
+/// dropped, so keep it as long as the callback should run.
+#[must_use = "the subscription ends when dropped"]
+pub struct ArgsSubscription {
+    #[cfg(all(target_arch = "wasm32", feature = "web-subscribe"))]
+    listener: wasm_bindgen::closure::Closure<dyn FnMut()>,
+}
+
+impl std::fmt::Debug for ArgsSubscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArgsSubscription").finish_non_exhaustive()
+    }
+}
+
+/// # Parse the args again each time the url changes
+///
+/// Registers `hashchange` and `popstate` listeners on the window that parse
+/// the web args into a fresh `T`, with the current config and arg source,
+/// and give the result to `callback`.  Help, version and errors come as the
+/// [`clap::Error`], nothing is printed and nothing exits.
+///
+/// The url is [read again](crate::refresh_args) before each parse, so the
+/// callback and any later parse see the new args.
+///
+/// The callback is not called for the args at subscribe time, parse those
+/// as usual first.  Dropping the returned [`ArgsSubscription`] removes the
+/// listeners.
+///
+/// Needs the "web-subscribe" feature on the web.  Without it, and on native
+/// where the args never change, the callback is never called.
+///
+/// # Example
+/// ``` rust
+/// use clap::Parser;
+///
+/// #[derive(Parser, Debug)]
+/// struct Opts {
+///     #[arg(long)]
+///     page: Option<u32>,
+/// }
+///
+/// let subscription = we_clap::subscribe_to_args(|opts: Result<Opts, clap::Error>| {
+///     if let Ok(opts) = opts {
+///         println!("now on page {:?}", opts.page);
+///     }
+/// });
+/// // Keep `subscription` alive for as long as the updates are wanted.
+/// drop(subscription);
+/// ```
+pub fn subscribe_to_args<T, F>(callback: F) -> ArgsSubscription
+where
+    T: Parser,
+    F: FnMut(Result<T, error::Error>) + 'static,
+{
+    #[cfg(all(target_arch = "wasm32", feature = "web-subscribe"))]
+    {
+        use wasm_bindgen::JsCast;
+
+        let mut callback = callback;
+        let listener = wasm_bindgen::closure::Closure::<dyn FnMut()>::new(move || {
+            callback(reparse::<T>());
+        });
+        if let Some(window) = web_sys::window() {
+            for event in EVENTS {
+                let _ = window
+                    .add_event_listener_with_callback(event, listener.as_ref().unchecked_ref());
+            }
+        }
+        ArgsSubscription { listener }
+    }
+    #[cfg(not(all(target_arch = "wasm32", feature = "web-subscribe")))]
+    {
+        let _ = callback;
+        ArgsSubscription {}
+    }
+}
+
+/// Parse the web args of the current url.
+#[cfg(all(target_arch = "wasm32", feature = "web-subscribe"))]
+fn reparse<T: Parser>() -> Result<T, error::Error> {
+    crate::refresh_args();
+    crate::translate::web_args(&T::command()).and_then(T::try_parse_from)
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-subscribe"))]
+impl Drop for ArgsSubscription {
+    fn drop(&mut self) {
+        use wasm_bindgen::JsCast;
+
+        if let Some(window) = web_sys::window() {
+            for event in EVENTS {
+                let _ = window.remove_event_listener_with_callback(
+                    event,
+                    self.listener.as_ref().unchecked_ref(),
+                );
+            }
+        }
+    }
+}
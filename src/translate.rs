@@ -0,0 +1,877 @@
+//! # Translate url query strings into command line arguments
+//!
+//! [`UrlArgs`](cliw::url_args::UrlArgs) slices a url into its path and decoded query strings.  The
+//! query strings are then translated for clap by the `no_std`
+//! [`query`](crate::query) core:
+//!
+//! * `key=value` becomes `--key=value`, or `-k=value` for a one letter key.
+//!   With [`Config::use_equals_form`] off it becomes `--key value`.
+//! * `_=value` becomes the positional `value`, see [`Config::positional_key`].
+//! * `key` or `key=`, for a flag that takes no value, becomes `--key`, or
+//!   `-k` for a one letter key.  So `?verbose&output=foo` works.
+//! * `key=3`, for a key in [`Config::count_params`], becomes `--key` three
+//!   times.
+//! * Anything else is passed through unchanged, so `?--key&value` still works.
+//!
+//! Each query string is its own arg, in order, so a repeated key is a
+//! repeated option.  `?tag=x&tag=y` becomes `--tag=x --tag=y`, and an
+//! [`Append`](clap::ArgAction::Append) or [`Count`](clap::ArgAction::Count)
+//! arg sees every occurrence.
+//!
+//! With the default equals form clap never mistakes a value like `-5` for
+//! a flag.  Control characters are then stripped, or rejected, see
+//! [`Config::control_chars`].
+//!
+//! Finally repeated keys for an arg that takes several values per
+//! occurrence, like `num_args = 2`, are grouped into one occurrence, with
+//! or without the equals form.  `?point=1&point=2` becomes `--point 1 2`.
+//! Grouped values are separate args again, so one starting with `-`, like
+//! `?point=-1&point=-2`, is an error unless the arg allows it with clap's
+//! `allow_hyphen_values`, or `allow_negative_numbers` for numbers.
+
+use crate::config::Config;
+#[cfg(target_arch = "wasm32")]
+use crate::perf;
+use crate::query::{flag, we_translate_queries};
+#[cfg(any(test, target_arch = "wasm32"))]
+use crate::sanitize::sanitize;
+use crate::sanitize::{strip, we_check_chars, ControlChars};
+use crate::source::program_name;
+#[cfg(target_arch = "wasm32")]
+use crate::source::{self, WebSource};
+use crate::WeError;
+#[cfg(target_arch = "wasm32")]
+use clap::error;
+use clap::{Arg, Command};
+#[cfg(test)]
+use cliw::url_args::UrlArgs;
+use std::cell::RefCell;
+use std::ffi::OsString;
+use std::rc::Rc;
+
+/// Rewrites the web args before clap sees them.
+type Preprocessor = Rc<dyn Fn(Vec<String>) -> Vec<String>>;
+
+thread_local! {
+    static PREPROCESSOR: RefCell<Option<Preprocessor>> = const { RefCell::new(None) };
+    static ALIASES: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// # Short url keys for long option names
+///
+/// Each `(key, long)` pair lets the url key `key` stand for the option
+/// `--long`, so links stay short while clap keeps descriptive names.  With
+/// `("q", "query")` the url `?q=rust` becomes `--query=rust`, and with
+/// `("v", "verbose")` a bare `?v` sets the `--verbose` flag.  The long
+/// name may be given with or without its `--`.
+///
+/// The keys are renamed before anything else is translated, so a
+/// [`Config::count_params`] entry names the long option.  Applies to
+/// every later web parse on the current thread, replacing the aliases set
+/// before.  Not used on native.
+///
+/// # Example
+/// ``` rust
+/// we_clap::set_arg_aliases(&[("q", "query"), ("lang", "language"), ("v", "--verbose")]);
+/// ```
+pub fn set_arg_aliases(aliases: &[(&str, &str)]) {
+    let aliases = aliases
+        .iter()
+        .map(|(key, long)| (key.to_string(), long.trim_start_matches('-').to_string()))
+        .collect();
+    ALIASES.with(|current| *current.borrow_mut() = aliases);
+}
+
+/// The url args with aliased query keys renamed, the path kept.
+fn unalias(url_args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    ALIASES.with(|aliases| {
+        let aliases = aliases.borrow();
+        let mut url_args = url_args.into_iter();
+        let path = url_args.next();
+        let queries = url_args.map(|query| {
+            let text = query.to_string_lossy();
+            let (key, value) = text
+                .split_once('=')
+                .map_or((&*text, None), |(key, value)| (key, Some(value)));
+            match aliases.iter().find(|(alias, _)| alias == key) {
+                Some((_, long)) => match value {
+                    Some(value) => OsString::from(format!("{long}={value}")),
+                    None => OsString::from(long),
+                },
+                None => query,
+            }
+        });
+        path.into_iter().chain(queries).collect()
+    })
+}
+
+/// # Rewrite the web args before parsing
+///
+/// `preprocessor` gets the web args just before clap parses them and
+/// returns the args to parse, an escape hatch for mapping legacy keys,
+/// folding case or injecting defaults.  It runs last, after the
+/// [program name](crate::set_program_name) is put first and the query
+/// strings are translated, so `?v=1` arrives as `-v=1` and a
+/// [`Config::count_params`] key as its repeated flag.  Web env values are
+/// in by then, the values of a config file are layered in later.
+///
+/// Applies to every later web parse on the current thread, replacing any
+/// preprocessor set before.  Native args are not rewritten.
+///
+/// # Example
+/// ``` rust
+/// // The old `?v` link means `--verbose` now.
+/// we_clap::set_arg_preprocessor(|args| {
+///     args.into_iter()
+///         .map(|arg| if arg == "-v" { "--verbose".to_string() } else { arg })
+///         .collect()
+/// });
+/// ```
+pub fn set_arg_preprocessor(preprocessor: impl Fn(Vec<String>) -> Vec<String> + 'static) {
+    PREPROCESSOR.with(|current| *current.borrow_mut() = Some(Rc::new(preprocessor)));
+}
+
+/// The args rewritten by the preprocessor, if one is set.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn preprocess(args: Vec<String>) -> Vec<String> {
+    match PREPROCESSOR.with(|preprocessor| preprocessor.borrow().clone()) {
+        Some(preprocessor) => preprocessor(args),
+        None => args,
+    }
+}
+
+/// Command line arguments, from [`std::env::ArgsOs`] on native or the
+/// webpage url on the web.
+pub(crate) fn args(command: &Command) -> Result<Vec<OsString>, WeError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = command;
+        Ok(std::env::args_os().collect())
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        crate::panic_hook::install();
+        let source = perf::timed(perf::READ, source::web_source)?;
+        let args = perf::timed(perf::TRANSLATE, || source_to_args(command, source))?;
+        Ok(args.into_iter().map(OsString::from).collect())
+    }
+}
+
+/// # The arguments given to clap, for logging
+///
+/// The arguments the parse functions, like
+/// [`we_parse`](crate::WeParser::we_parse()), hand to clap.  Log them to
+/// see what a url decoded to.\
+/// Native args are from [`std::env::ArgsOs`], argv\[0\] first.\
+/// Web args are from [`cliw::url_args::UrlArgs`], translated with the
+/// current [`Config`](crate::Config).  The first arg is the
+/// [program name](crate::set_program_name) when one is set, else the page
+/// url up to the query string, the command name is not known here.
+///
+/// The web grouping of repeated keys for args with `num_args` above one
+/// needs the command, it is not done here.  A command line saved for
+/// [`ArgSource::LocalStorage`](crate::ArgSource::LocalStorage) is split,
+/// not translated, and gives no args when it doesn't split.
+///
+/// # Example
+/// ``` rust
+/// let args = we_clap::we_args();
+/// println!("parsing {args:?}");
+/// ```
+#[must_use]
+pub fn we_args() -> Vec<String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::env::args_os()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let mut args = match source::web_source() {
+            Ok(WebSource::Url(url_args)) => translate(unalias(url_args), &crate::config()),
+            Ok(WebSource::Args(args)) => args,
+            Err(_) => Vec::new(),
+        };
+        if let (Some(first), Some(name)) = (args.first_mut(), program_name()) {
+            *first = name;
+        }
+        args
+    }
+}
+
+/// Command line arguments from the webpage url.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn web_args(command: &Command) -> error::Result<Vec<String>> {
+    crate::panic_hook::install();
+    perf::timed(perf::READ, source::web_source)
+        .and_then(|source| perf::timed(perf::TRANSLATE, || source_to_args(command, source)))
+        .map_err(|err| err.into_clap(command))
+}
+
+/// Command line arguments for a command from the web source, translated
+/// for a url.
+#[cfg(target_arch = "wasm32")]
+fn source_to_args(command: &Command, source: WebSource) -> Result<Vec<String>, WeError> {
+    #[cfg(feature = "web-path")]
+    source::check_path_subcommand(command)?;
+    let mut args = match source {
+        WebSource::Url(url_args) => url_to_args(command, url_args)?,
+        WebSource::Args(args) => given_args(command, args)?,
+    };
+    #[cfg(feature = "web-env")]
+    {
+        args = crate::env::env_fallback(command, args, crate::we_env);
+    }
+    if let Some(choice) = crate::style::take_color(command, &mut args) {
+        crate::style::set_color(choice);
+    }
+    let args = preprocess(args);
+    crate::debug::log_args(&args);
+    Ok(args)
+}
+
+/// Command line arguments for a command from the path and decoded query
+/// strings of a url, with the current config.
+///
+/// Fails on control characters with [`ControlChars::Reject`].
+pub(crate) fn url_to_args(
+    command: &Command,
+    url_args: impl IntoIterator<Item = OsString>,
+) -> Result<Vec<String>, WeError> {
+    let config = crate::config();
+    let url_args = presence_flags(command, unalias(url_args));
+    let mut args = group_values(command, translate(url_args, &config))?;
+    if config.control_chars == ControlChars::Reject {
+        we_check_chars(&args)?;
+    }
+    name_program(command, &mut args);
+    Ok(args)
+}
+
+/// Command line arguments for a command from args that need no
+/// translation, like a split `?args=` value or a JSON object, with the
+/// current config.
+///
+/// Control characters are stripped or rejected like those of a url.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) fn given_args(command: &Command, mut args: Vec<String>) -> Result<Vec<String>, WeError> {
+    sanitize(&mut args, crate::config().control_chars)?;
+    name_program(command, &mut args);
+    Ok(args)
+}
+
+/// Put the [program name](crate::set_program_name) in place of the page
+/// url as the first arg, the name clap shows in usage.
+pub(crate) fn name_program(command: &Command, args: &mut Vec<String>) {
+    let name = program_name().unwrap_or_else(|| command.get_name().to_string());
+    match args.first_mut() {
+        Some(first) => *first = name,
+        None => args.push(name),
+    }
+}
+
+/// Command line arguments from the document referrer.
+///
+/// No referrer gives an empty url, which gives no arguments.
+#[cfg(all(target_arch = "wasm32", feature = "web-referrer"))]
+pub(crate) fn referrer_args(command: &Command) -> error::Result<Vec<String>> {
+    crate::panic_hook::install();
+    let referrer = web_sys::window()
+        .and_then(|window| window.document())
+        .map(|document| document.referrer())
+        .unwrap_or_default();
+    url_to_args(command, crate::source::from_url(&referrer))
+        .map(preprocess)
+        .map_err(|err| err.into_clap(command))
+}
+
+/// Translate the path and query strings from [`UrlArgs`](cliw::url_args::UrlArgs).
+fn translate(url_args: impl IntoIterator<Item = OsString>, config: &Config) -> Vec<String> {
+    let mut url_args = url_args
+        .into_iter()
+        .map(|arg| arg.to_string_lossy().into_owned());
+    let mut args: Vec<String> = url_args.next().into_iter().collect();
+    let queries: Vec<String> = url_args
+        .flat_map(|query| expand_count(query, config))
+        .collect();
+    args.extend(we_translate_queries(
+        queries.iter().map(String::as_str),
+        &config.query_rules(),
+    ));
+    if config.control_chars == ControlChars::Strip {
+        args.iter_mut().for_each(|arg| *arg = strip(arg));
+    }
+    args
+}
+
+/// Expand a `key=n` query string for a [`Config::count_params`] key into
+/// the flag `n` times.
+fn expand_count(query: String, config: &Config) -> Vec<String> {
+    let count = query.split_once('=').and_then(|(key, value)| {
+        let counted = config.count_params.iter().any(|param| param == key);
+        counted
+            .then(|| value.parse::<u8>().ok())
+            .flatten()
+            .map(|n| (key, n))
+    });
+    match count {
+        Some((key, n)) => vec![flag(key); usize::from(n)],
+        None => vec![query],
+    }
+}
+
+/// Turn presence only query strings, `verbose` or `verbose=`, into
+/// `--verbose` for flags that take no value.
+///
+/// A bare query string naming a subcommand stays a subcommand, and any
+/// other query string is left for [`translate`].
+fn presence_flags(
+    command: &Command,
+    url_args: impl IntoIterator<Item = OsString>,
+) -> Vec<OsString> {
+    let mut command = command;
+    let mut url_args = url_args.into_iter();
+    let mut args: Vec<OsString> = url_args.next().into_iter().collect();
+
+    for query in url_args {
+        let Some(text) = query.to_str() else {
+            args.push(query);
+            continue;
+        };
+        if let Some(subcommand) = command.find_subcommand(text) {
+            command = subcommand;
+            args.push(query);
+            continue;
+        }
+        let key = text.strip_suffix('=').unwrap_or(text);
+        match no_value_flag(command, key) {
+            Some(flag) => args.push(flag.into()),
+            None => args.push(query),
+        }
+    }
+    args
+}
+
+/// The flag for a query key naming an arg that takes no value.
+fn no_value_flag(command: &Command, key: &str) -> Option<String> {
+    if key.is_empty() || key.starts_with('-') || key.contains('=') {
+        return None;
+    }
+    let arg = command.get_arguments().find(|arg| {
+        arg.get_long() == Some(key)
+            || arg
+                .get_short()
+                .is_some_and(|short| key == short.to_string())
+    })?;
+    if arg.get_action().takes_values() {
+        return None;
+    }
+    if arg.get_long() == Some(key) {
+        Some(format!("--{key}"))
+    } else {
+        Some(format!("-{key}"))
+    }
+}
+
+/// Group repeated `--flag=value` args, or `--flag value` without the
+/// equals form, into one `--flag value...` occurrence for args that need
+/// several values per occurrence.
+///
+/// Each occurrence takes up to the most values the arg accepts.  Too few
+/// values are left for clap to report.  A grouped value starting with `-`
+/// would be taken for a flag, it is an error unless the arg allows it with
+/// `allow_hyphen_values`, or `allow_negative_numbers` for a number.
+fn group_values(command: &Command, args: Vec<String>) -> Result<Vec<String>, WeError> {
+    let mut command = command;
+    let mut args = args.into_iter().peekable();
+    let mut grouped: Vec<String> = args.next().into_iter().collect();
+
+    while let Some(arg) = args.next() {
+        if let Some(subcommand) = command.find_subcommand(&arg) {
+            command = subcommand;
+            grouped.push(arg);
+            continue;
+        }
+        let flag = arg.split_once('=').map_or(arg.as_str(), |(flag, _)| flag);
+        let Some(multi) = multi_value_arg(command, flag) else {
+            grouped.push(arg);
+            continue;
+        };
+        let flag = flag.to_string();
+        let max_values = multi.get_num_args().map_or(1, |range| range.max_values());
+        let mut values = Vec::new();
+        let mut next = Some(arg);
+        while let Some(arg) = next {
+            let Some(value) = occurrence_value(&arg, &flag, &mut args) else {
+                grouped.push(arg);
+                break;
+            };
+            if value.starts_with('-') && !allows_hyphen(multi, &value) {
+                let message = format!(
+                    "the value '{value}' for '{flag}' starts with '-', on the web it can't \
+                     be one of several values unless the arg allows hyphen values\n"
+                );
+                return Err(
+                    clap::Error::raw(clap::error::ErrorKind::InvalidValue, message)
+                        .format(&mut command.clone())
+                        .into(),
+                );
+            }
+            values.push(value);
+            next = if values.len() < max_values {
+                args.next_if(|next| next == &flag || next.starts_with(&format!("{flag}=")))
+            } else {
+                None
+            };
+        }
+        if !values.is_empty() {
+            grouped.push(flag);
+            grouped.extend(values);
+        }
+    }
+    Ok(grouped)
+}
+
+/// The value of one occurrence of a flag, `--flag=value` or the next arg
+/// after `--flag`.
+fn occurrence_value(
+    arg: &str,
+    flag: &str,
+    args: &mut std::iter::Peekable<std::vec::IntoIter<String>>,
+) -> Option<String> {
+    if arg == flag {
+        args.next()
+    } else {
+        arg.strip_prefix(flag)?
+            .strip_prefix('=')
+            .map(str::to_string)
+    }
+}
+
+/// Whether a multi value arg takes a value starting with `-`.
+fn allows_hyphen(arg: &Arg, value: &str) -> bool {
+    arg.is_allow_hyphen_values_set()
+        || (arg.is_allow_negative_numbers_set() && value.parse::<f64>().is_ok())
+}
+
+/// Move named positional values, `--src=a` from `?src=a`, into their
+/// positional slots in the order the command defines them.
+///
+/// A key names a positional when it is the id of a positional arg and no
+/// flag has that name.  The values of each command level are placed before
+/// its subcommand, or at the end.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) fn name_positionals(command: &Command, args: Vec<String>) -> Vec<String> {
+    let mut command = command;
+    let mut args = args.into_iter();
+    let mut named: Vec<String> = args.next().into_iter().collect();
+    let mut slots: Vec<Vec<String>> = vec![Vec::new(); command.get_positionals().count()];
+
+    while let Some(arg) = args.next() {
+        if let Some(subcommand) = command.find_subcommand(&arg) {
+            named.extend(slots.drain(..).flatten());
+            command = subcommand;
+            slots = vec![Vec::new(); command.get_positionals().count()];
+            named.push(arg);
+            continue;
+        }
+        let (key, value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value)),
+            None => (arg.as_str(), None),
+        };
+        let Some(slot) = positional_slot(command, key) else {
+            named.push(arg);
+            continue;
+        };
+        match value.map(String::from).or_else(|| args.next()) {
+            Some(value) => slots[slot].push(value),
+            None => named.push(arg),
+        }
+    }
+    named.extend(slots.into_iter().flatten());
+    named
+}
+
+/// The slot of the positional named by a flag, like `--src`.
+#[cfg(any(test, target_arch = "wasm32"))]
+fn positional_slot(command: &Command, flag: &str) -> Option<usize> {
+    let key = flag.strip_prefix("--").or_else(|| flag.strip_prefix('-'))?;
+    let is_flag = command.get_arguments().any(|arg| {
+        arg.get_long() == Some(key)
+            || arg
+                .get_short()
+                .is_some_and(|short| key == short.to_string())
+    });
+    if is_flag {
+        return None;
+    }
+    command
+        .get_positionals()
+        .position(|arg| arg.get_id() == key)
+}
+
+/// The arg of a flag, if it needs more than one value per occurrence.
+fn multi_value_arg<'a>(command: &'a Command, flag: &str) -> Option<&'a Arg> {
+    let arg = if let Some(long) = flag.strip_prefix("--") {
+        command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(long))
+    } else {
+        let mut short = flag.strip_prefix('-')?.chars();
+        match (short.next(), short.next()) {
+            (Some(short), None) => command
+                .get_arguments()
+                .find(|arg| arg.get_short() == Some(short)),
+            _ => None,
+        }
+    }?;
+    (arg.get_num_args()?.min_values() > 1).then_some(arg)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// Translate a url with the default config.
+    pub(crate) fn url_args(url: &str) -> Vec<String> {
+        translate(crate::source::from_url(url), &Config::default())
+    }
+
+    #[derive(Parser, Debug)]
+    struct Opts {
+        #[arg(short, long, default_value_t = 0)]
+        offset: i32,
+        #[arg(short, long)]
+        name: Option<String>,
+    }
+
+    #[test]
+    fn preprocessor() {
+        let args = vec!["app".to_string(), "-v=1".to_string()];
+        assert_eq!(preprocess(args.clone()), args);
+        set_arg_preprocessor(|args| {
+            args.into_iter()
+                .map(|arg| arg.replace("-v=", "--verbose="))
+                .collect()
+        });
+        assert_eq!(preprocess(args), ["app", "--verbose=1"]);
+        PREPROCESSOR.with(|preprocessor| preprocessor.borrow_mut().take());
+    }
+
+    #[test]
+    fn aliases() {
+        let command = Command::new("app")
+            .arg(Arg::new("query").long("query"))
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .action(clap::ArgAction::SetTrue),
+            );
+        let args = |url: &str| url_to_args(&command, crate::source::from_url(url)).unwrap();
+        assert_eq!(args("/?q=rust&v"), ["app", "-q=rust", "v"]);
+        set_arg_aliases(&[("q", "query"), ("v", "--verbose")]);
+        assert_eq!(args("/?q=rust&v"), ["app", "--query=rust", "--verbose"]);
+        assert_eq!(args("/?v=&query=x"), ["app", "--verbose", "--query=x"]);
+        set_arg_aliases(&[]);
+    }
+
+    #[test]
+    fn key_value() {
+        let args = url_args("http://example.org/index.html?offset=3&n=bob&--verbose&file");
+        assert_eq!(
+            args,
+            [
+                "http://example.org/index.html",
+                "--offset=3",
+                "-n=bob",
+                "--verbose",
+                "file"
+            ]
+        );
+    }
+
+    #[test]
+    fn negative_number() {
+        let opts = Opts::try_parse_from(url_args("http://example.org/?offset=-5")).unwrap();
+        assert_eq!(opts.offset, -5);
+
+        let opts = Opts::try_parse_from(url_args("http://example.org/?o=-5")).unwrap();
+        assert_eq!(opts.offset, -5);
+    }
+
+    #[test]
+    fn equals_form() {
+        let opts = Opts::try_parse_from(url_args("http://example.org/?name=-bob")).unwrap();
+        assert_eq!(opts.name.as_deref(), Some("-bob"));
+
+        let opts = Opts::try_parse_from(url_args("http://example.org/?name=--x=1")).unwrap();
+        assert_eq!(opts.name.as_deref(), Some("--x=1"));
+    }
+
+    #[test]
+    fn positional_key() {
+        let args = url_args("/?_=a&o=1&_=b");
+        assert_eq!(args, ["/", "a", "-o=1", "b"]);
+
+        let config = Config {
+            positional_delimiter: Some('|'),
+            ..Config::default()
+        };
+        let args = translate(UrlArgs::from("/?_=a|b|c"), &config);
+        assert_eq!(args, ["/", "a", "b", "c"]);
+
+        let args = translate(UrlArgs::from("/?_=a|b&_=c"), &config);
+        assert_eq!(args, ["/", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn repeated_keys() {
+        use clap::{ArgAction, CommandFactory, Parser};
+
+        #[derive(Parser, Debug)]
+        struct Opts {
+            #[arg(long)]
+            tag: Vec<String>,
+            #[arg(short, long, action = ArgAction::Count)]
+            verbose: u8,
+        }
+
+        let url = UrlArgs::from("/?tag=x&verbose&tag=y&v&tag=z");
+        let args = url_to_args(&Opts::command(), url).unwrap();
+        assert_eq!(
+            args[1..],
+            ["--tag=x", "--verbose", "--tag=y", "-v", "--tag=z"]
+        );
+        let opts = Opts::try_parse_from(args).unwrap();
+        assert_eq!(opts.tag, ["x", "y", "z"]);
+        assert_eq!(opts.verbose, 2);
+    }
+
+    #[test]
+    fn num_args() {
+        use clap::{error::ErrorKind, ArgAction};
+
+        let command = Command::new("plot").arg(Arg::new("point").long("point").num_args(2));
+        let args = group_values(&command, url_args("/?point=1&point=2")).unwrap();
+        assert_eq!(args, ["/", "--point", "1", "2"]);
+        let matches = command.clone().try_get_matches_from(args).unwrap();
+        let point: Vec<&String> = matches.get_many("point").unwrap().collect();
+        assert_eq!(point, ["1", "2"]);
+
+        let args = group_values(&command, url_args("/?point=1")).unwrap();
+        let err = command.try_get_matches_from(args).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WrongNumberOfValues);
+
+        let command = Command::new("plot").subcommand(
+            Command::new("line").arg(
+                Arg::new("point")
+                    .short('p')
+                    .num_args(2..=3)
+                    .action(ArgAction::Append),
+            ),
+        );
+        let args = group_values(&command, url_args("/?line&p=1&p=2&p=3&p=4&p=5")).unwrap();
+        assert_eq!(args, ["/", "line", "-p", "1", "2", "3", "-p", "4", "5"]);
+        assert!(command.try_get_matches_from(args).is_ok());
+    }
+
+    #[test]
+    fn num_args_hyphen_values() {
+        let point = Arg::new("point").long("point").num_args(2);
+        let command = Command::new("plot").arg(point.clone());
+        let err = group_values(&command, url_args("/?point=-1&point=-2")).unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.contains("'-1' for '--point' starts with '-'"),
+            "{message}"
+        );
+
+        let command = Command::new("plot").arg(point.clone().allow_negative_numbers(true));
+        let args = group_values(&command, url_args("/?point=-1&point=-2")).unwrap();
+        assert_eq!(args, ["/", "--point", "-1", "-2"]);
+        let matches = command.clone().try_get_matches_from(args).unwrap();
+        let values: Vec<&String> = matches.get_many("point").unwrap().collect();
+        assert_eq!(values, ["-1", "-2"]);
+        assert!(group_values(&command, url_args("/?point=-v&point=1")).is_err());
+
+        let command = Command::new("plot").arg(point.allow_hyphen_values(true));
+        let args = group_values(&command, url_args("/?point=-v&point=1")).unwrap();
+        assert!(command.try_get_matches_from(args).is_ok());
+    }
+
+    #[test]
+    fn num_args_separate_form() {
+        let config = Config {
+            use_equals_form: false,
+            ..Config::default()
+        };
+        let command = Command::new("plot")
+            .arg(Arg::new("point").long("point").num_args(2))
+            .arg(Arg::new("name").long("name"));
+        let args = translate(
+            UrlArgs::from("/?point=1&name=a&point=2&point=3&point=4"),
+            &config,
+        );
+        assert_eq!(
+            args,
+            ["/", "--point", "1", "--name", "a", "--point", "2", "--point", "3", "--point", "4"]
+        );
+        let args = group_values(&command, args).unwrap();
+        assert_eq!(
+            args,
+            ["/", "--point", "1", "--name", "a", "--point", "2", "3", "--point", "4"]
+        );
+
+        let args = translate(UrlArgs::from("/?point=1&point=2&name=a"), &config);
+        let args = group_values(&command, args).unwrap();
+        assert_eq!(args, ["/", "--point", "1", "2", "--name", "a"]);
+        let matches = command.try_get_matches_from(args).unwrap();
+        let values: Vec<&String> = matches.get_many("point").unwrap().collect();
+        assert_eq!(values, ["1", "2"]);
+    }
+
+    #[test]
+    fn referrer() {
+        let args = url_args("https://host.example/page.html?offset=2&name=widget");
+        let opts = Opts::try_parse_from(args).unwrap();
+        assert_eq!(opts.offset, 2);
+        assert_eq!(opts.name.as_deref(), Some("widget"));
+
+        // Missing and cross-origin referrers have no query.
+        for referrer in ["", "https://other.example/"] {
+            let opts = Opts::try_parse_from(url_args(referrer)).unwrap();
+            assert_eq!(opts.offset, 0);
+            assert_eq!(opts.name, None);
+        }
+    }
+
+    #[test]
+    fn separate_form() {
+        let config = Config {
+            use_equals_form: false,
+            ..Config::default()
+        };
+        let args = translate(UrlArgs::from("/?name=bob&o=1"), &config);
+        assert_eq!(args, ["/", "--name", "bob", "-o", "1"]);
+
+        let args = translate(UrlArgs::from("/?name=-bob"), &config);
+        assert!(Opts::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn hyphen_values() {
+        let command = Command::new("calc")
+            .arg(Arg::new("expr").long("expr").allow_hyphen_values(true))
+            .arg(Arg::new("term").allow_hyphen_values(true));
+        let separate = Config {
+            use_equals_form: false,
+            ..Config::default()
+        };
+
+        for config in [Config::default(), separate] {
+            let args = translate(UrlArgs::from("/?expr=-x%2B1&_=-y"), &config);
+            let matches = command.clone().try_get_matches_from(args).unwrap();
+            assert_eq!(matches.get_one::<String>("expr").unwrap(), "-x+1");
+            assert_eq!(matches.get_one::<String>("term").unwrap(), "-y");
+        }
+    }
+
+    #[test]
+    fn presence_only_flags() {
+        use clap::ArgAction;
+
+        let command = Command::new("app")
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .short('v')
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(Arg::new("quiet").long("quiet").action(ArgAction::SetTrue))
+            .arg(Arg::new("output").long("output"))
+            .arg(Arg::new("file"))
+            .subcommand(
+                Command::new("run").arg(Arg::new("fast").long("fast").action(ArgAction::SetTrue)),
+            );
+        let args = |url| url_to_args(&command, UrlArgs::from(url)).unwrap();
+
+        assert_eq!(
+            args("/?verbose&quiet=&output=foo"),
+            ["app", "--verbose", "--quiet", "--output=foo"]
+        );
+        let matches = command
+            .clone()
+            .try_get_matches_from(args("/?verbose&quiet=&output=foo"))
+            .unwrap();
+        assert!(matches.get_flag("verbose"));
+        assert!(matches.get_flag("quiet"));
+        assert_eq!(matches.get_one::<String>("output").unwrap(), "foo");
+
+        assert_eq!(args("/?v"), ["app", "-v"]);
+        assert_eq!(args("/?output=&_=verbose"), ["app", "--output=", "verbose"]);
+        assert_eq!(args("/?run&fast&file"), ["app", "run", "--fast", "file"]);
+    }
+
+    #[test]
+    fn count_params() {
+        use clap::ArgAction;
+
+        let command = Command::new("app")
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .short('v')
+                    .action(ArgAction::Count),
+            )
+            .arg(Arg::new("level").long("level"));
+        let config = Config {
+            count_params: vec!["v".into(), "verbose".into()],
+            ..Config::default()
+        };
+        let args = |url| translate(UrlArgs::from(url), &config);
+
+        assert_eq!(args("/?verbose=2"), ["/", "--verbose", "--verbose"]);
+        assert_eq!(args("/?v=3&level=2"), ["/", "-v", "-v", "-v", "--level=2"]);
+        assert_eq!(args("/?v=0"), ["/"]);
+        assert_eq!(args("/?v=lots&v=256"), ["/", "-v=lots", "-v=256"]);
+        let matches = command
+            .clone()
+            .try_get_matches_from(args("/?verbose=2&v=1"))
+            .unwrap();
+        assert_eq!(matches.get_count("verbose"), 3);
+
+        // Not a count param, and a repeated bare key.
+        assert_eq!(url_args("/?v=3"), ["/", "-v=3"]);
+        let args = url_to_args(&command, UrlArgs::from("/?v&v&verbose")).unwrap();
+        assert_eq!(args, ["app", "-v", "-v", "--verbose"]);
+    }
+
+    #[test]
+    fn named_positionals() {
+        let command = Command::new("copy")
+            .arg(Arg::new("src").required(true))
+            .arg(Arg::new("dst").required(true))
+            .arg(Arg::new("force").long("force").short('f'))
+            .subcommand(Command::new("log").arg(Arg::new("file")));
+
+        for url in ["/?src=a&dst=b", "/?dst=b&src=a"] {
+            let args = name_positionals(&command, url_args(url));
+            assert_eq!(args, ["/", "a", "b"]);
+        }
+
+        let args = name_positionals(&command, url_args("/?dst=b&force=1&src=a&log&file=x"));
+        assert_eq!(args, ["/", "--force=1", "a", "b", "log", "x"]);
+        let matches = command.clone().try_get_matches_from(args).unwrap();
+        assert_eq!(matches.get_one::<String>("src").unwrap(), "a");
+        assert_eq!(matches.get_one::<String>("dst").unwrap(), "b");
+
+        let config = Config {
+            use_equals_form: false,
+            ..Config::default()
+        };
+        let args = translate(UrlArgs::from("/?dst=b&src=a"), &config);
+        assert_eq!(name_positionals(&command, args), ["/", "a", "b"]);
+    }
+}
@@ -0,0 +1,88 @@
+//! # Report every unexpected arg at once
+//!
+//! Clap stops at the first unexpected arg.  Someone fixing a shared link
+//! would rather see all of them, see
+//! [`Config::report_all_unexpected`](crate::Config::report_all_unexpected).
+
+use clap::error::{ContextKind, ContextValue, Error, ErrorKind};
+use clap::Command;
+
+/// Replace an unexpected argument error with one naming every unexpected
+/// arg.
+///
+/// Probes by removing the unexpected arg and parsing again, until a parse
+/// finds no more.  Other errors, or a single unexpected arg, are returned
+/// unchanged.
+pub(crate) fn all_unexpected(command: &Command, args: &[String], err: Error) -> Error {
+    let mut args = args.to_vec();
+    let mut unexpected = Vec::new();
+    let mut invalid = invalid_arg(&err);
+
+    while let Some(arg) = invalid.take() {
+        let flag = format!("{arg}=");
+        let Some(index) = args
+            .iter()
+            .skip(1)
+            .position(|token| *token == arg || token.starts_with(&flag))
+        else {
+            break;
+        };
+        args.remove(index + 1);
+        unexpected.push(arg);
+        if let Err(next) = command.clone().try_get_matches_from(&args) {
+            invalid = invalid_arg(&next);
+        }
+    }
+
+    if unexpected.len() < 2 {
+        return err;
+    }
+    let list: Vec<String> = unexpected.iter().map(|arg| format!("'{arg}'")).collect();
+    Error::raw(
+        ErrorKind::UnknownArgument,
+        format!("unexpected arguments found: {}\n", list.join(", ")),
+    )
+    .format(&mut command.clone())
+}
+
+/// The unexpected arg of an unknown argument error.
+fn invalid_arg(err: &Error) -> Option<String> {
+    if err.kind() != ErrorKind::UnknownArgument {
+        return None;
+    }
+    match err.get(ContextKind::InvalidArg) {
+        Some(ContextValue::String(arg)) => Some(arg.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translate::tests::url_args;
+    use clap::Arg;
+
+    fn command() -> Command {
+        Command::new("app").arg(Arg::new("name").long("name"))
+    }
+
+    #[test]
+    fn two_unexpected() {
+        let args = url_args("/?colour=red&name=bob&sise=3");
+        let err = command().try_get_matches_from(&args).unwrap_err();
+        let err = all_unexpected(&command(), &args, err);
+
+        let msg = err.to_string();
+        assert_eq!(err.kind(), ErrorKind::UnknownArgument);
+        assert!(msg.contains("'--colour', '--sise'"), "{msg}");
+    }
+
+    #[test]
+    fn one_unexpected() {
+        let args = url_args("/?colour=red&name=bob");
+        let err = command().try_get_matches_from(&args).unwrap_err();
+        let msg = err.to_string();
+        let err = all_unexpected(&command(), &args, err);
+        assert_eq!(err.to_string(), msg);
+    }
+}
@@ -0,0 +1,215 @@
+//! # Errors from we_clap
+//!
+//! Most functions return a plain [`clap::Error`], like clap.  Functions
+//! that can fail for reasons of their own return a [`WeError`].  A
+//! [`RenderedError`] is a clap error with its message already rendered.
+
+use crate::OutputKind;
+use clap::error::ErrorKind;
+use std::fmt;
+use std::ops::Deref;
+
+/// # An error from we_clap
+///
+/// Either a [`clap::Error`] from parsing, or a reason of we_clap's own.
+/// More reasons may be added, so a `match` needs a wildcard arm.
+///
+/// # Example
+/// ``` rust
+/// let err = we_clap::WeError::Aborted;
+/// assert_eq!(err.to_string(), "parsing was aborted");
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum WeError {
+    /// Clap failed to parse, or help or version was requested.
+    Clap(clap::Error),
+    /// Parsing was aborted before the args arrived.
+    Aborted,
+    /// The args were rejected by a validator, with the reason.
+    Rejected(String),
+    /// An arg holds a control character, see
+    /// [`ControlChars::Reject`](crate::ControlChars::Reject).  The arg is
+    /// given with its control characters escaped.
+    InvalidCharacter(String),
+    /// A command line given as one string didn't split, see
+    /// [`shell_split`](crate::shell_split).
+    Split(crate::SplitError),
+    /// A JSON object of args didn't parse, see
+    /// [`ArgSource::Json`](crate::ArgSource::Json).
+    Json(String),
+    /// The first url path segment isn't a subcommand, with
+    /// [`Config::strict_path_subcommands`](crate::Config::strict_path_subcommands).
+    UnknownSubcommand(String),
+}
+
+impl fmt::Display for WeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Clap(err) => write!(f, "{err}"),
+            Self::Aborted => write!(f, "parsing was aborted"),
+            Self::Rejected(reason) => write!(f, "args rejected: {reason}"),
+            Self::InvalidCharacter(arg) => write!(f, "invalid character in argument '{arg}'"),
+            Self::Split(err) => write!(f, "args don't split: {err}"),
+            Self::Json(reason) => write!(f, "json args don't parse: {reason}"),
+            Self::UnknownSubcommand(name) => {
+                write!(f, "unrecognized subcommand '{name}' in the url path")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Clap(err) => Some(err),
+            Self::Split(err) => Some(err),
+            Self::Aborted
+            | Self::Rejected(_)
+            | Self::InvalidCharacter(_)
+            | Self::Json(_)
+            | Self::UnknownSubcommand(_) => None,
+        }
+    }
+}
+
+impl From<clap::Error> for WeError {
+    fn from(err: clap::Error) -> Self {
+        Self::Clap(err)
+    }
+}
+
+impl From<crate::SplitError> for WeError {
+    fn from(err: crate::SplitError) -> Self {
+        Self::Split(err)
+    }
+}
+
+impl WeError {
+    /// A clap error for functions that return one, formatted for the
+    /// command.
+    pub(crate) fn into_clap(self, command: &clap::Command) -> clap::Error {
+        match self {
+            Self::Clap(err) => err,
+            Self::UnknownSubcommand(_) => clap::Error::raw(
+                clap::error::ErrorKind::InvalidSubcommand,
+                format!("{self}\n"),
+            )
+            .format(&mut command.clone()),
+            err => clap::Error::raw(clap::error::ErrorKind::InvalidValue, format!("{err}\n"))
+                .format(&mut command.clone()),
+        }
+    }
+}
+
+/// # A clap error, rendered and classified
+///
+/// The [`clap::Error`] with its message as plain text and where we_clap
+/// would send it, for apps that log the kind and show the message
+/// themselves.  From
+/// [`WeParser::we_try_parse_rendered()`](crate::WeParser::we_try_parse_rendered()),
+/// or any clap error with `into()`.
+///
+/// It derefs to the [`clap::Error`], so `err.kind()` and friends work as
+/// before, and turns back into one with `into()`.
+///
+/// # Example
+/// ``` rust
+/// use clap::error::ErrorKind;
+/// use clap::Command;
+/// use we_clap::{OutputKind, RenderedError};
+///
+/// let err: RenderedError = Command::new("app")
+///     .try_get_matches_from(["app", "--nope"])
+///     .unwrap_err()
+///     .into();
+///
+/// assert_eq!(err.kind(), ErrorKind::UnknownArgument);
+/// assert_eq!(err.output, OutputKind::Stderr);
+/// assert!(err.rendered.starts_with("error: unexpected argument '--nope'"));
+/// ```
+#[derive(Debug)]
+pub struct RenderedError {
+    /// The clap error.
+    pub inner: clap::Error,
+    /// The message as plain text, without ANSI colors.
+    pub rendered: String,
+    /// The kind of the error.
+    pub kind: ErrorKind,
+    /// Where the message goes, [`OutputKind::Stdout`] for help and
+    /// version, [`OutputKind::Stderr`] for failures.
+    pub output: OutputKind,
+}
+
+impl From<clap::Error> for RenderedError {
+    fn from(inner: clap::Error) -> Self {
+        let output = if inner.use_stderr() {
+            OutputKind::Stderr
+        } else {
+            OutputKind::Stdout
+        };
+        Self {
+            rendered: inner.render().to_string(),
+            kind: inner.kind(),
+            output,
+            inner,
+        }
+    }
+}
+
+impl From<RenderedError> for clap::Error {
+    fn from(err: RenderedError) -> Self {
+        err.inner
+    }
+}
+
+impl Deref for RenderedError {
+    type Target = clap::Error;
+
+    fn deref(&self) -> &clap::Error {
+        &self.inner
+    }
+}
+
+impl fmt::Display for RenderedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rendered)
+    }
+}
+
+impl std::error::Error for RenderedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ColorChoice, Command};
+
+    #[test]
+    fn rendered_and_classified() {
+        let command = Command::new("app")
+            .version("1.0")
+            .color(ColorChoice::Always)
+            .arg(Arg::new("name").long("name").required(true));
+
+        let err = RenderedError::from(command.clone().try_get_matches_from(["app"]).unwrap_err());
+        assert_eq!(err.kind, ErrorKind::MissingRequiredArgument);
+        assert_eq!(err.kind(), err.kind);
+        assert_eq!(err.output, OutputKind::Stderr);
+        assert!(!err.rendered.contains('\x1b'));
+        assert_eq!(err.to_string(), err.rendered);
+
+        let err = RenderedError::from(
+            command
+                .try_get_matches_from(["app", "--version"])
+                .unwrap_err(),
+        );
+        assert_eq!(err.output, OutputKind::Stdout);
+        assert_eq!(err.rendered, "app 1.0\n");
+        let inner: clap::Error = err.into();
+        assert_eq!(inner.kind(), ErrorKind::DisplayVersion);
+    }
+}
@@ -0,0 +1,148 @@
+//! # A reusable snapshot of the arguments
+//!
+//! Apps that build their [`Command`] from fetched metadata can change it
+//! while the page is open.  A [`WebArgs`] keeps the url, so the same args
+//! can be parsed again against the new command without a reload.
+
+#[cfg(target_arch = "wasm32")]
+use crate::source::WebSource;
+use clap::{error, ArgMatches, Command};
+use std::ffi::OsString;
+
+/// # The command line arguments, kept for parsing again
+///
+/// A snapshot of the command line on native, or of the url on the web,
+/// taken when it is created.  [`reparse`](WebArgs::reparse) parses the
+/// snapshot against any command.  On the web the url is translated for
+/// each command, with the current [`Config`](crate::Config), so args that
+/// take several values are grouped for the command given.
+///
+/// The snapshot is reused as is.  If the url may have changed, by
+/// `history.pushState` for example, call [`refresh`](WebArgs::refresh)
+/// first.
+///
+/// # Example
+/// ``` rust
+/// use clap::{Arg, Command};
+/// use we_clap::WebArgs;
+///
+/// let args = WebArgs::from_url("https://example.org/app.html?size=3");
+///
+/// let old = Command::new("app").arg(Arg::new("size").long("size"));
+/// assert!(args.reparse(old).is_ok());
+///
+/// // The metadata changed, parse the same url again.
+/// let new = Command::new("app").arg(Arg::new("width").long("width"));
+/// assert!(args.reparse(new).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct WebArgs {
+    args: Vec<OsString>,
+    url: bool,
+}
+
+impl WebArgs {
+    /// Snapshot the arguments.\
+    /// Native args are from [`std::env::ArgsOs`].\
+    /// Web args are from [`cliw::url_args::UrlArgs`], or the saved command
+    /// line of [`ArgSource::LocalStorage`](crate::ArgSource::LocalStorage),
+    /// none if it doesn't split.
+    #[must_use]
+    pub fn new() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Self {
+                args: std::env::args_os().collect(),
+                url: false,
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            match crate::source::web_source() {
+                Ok(WebSource::Url(url_args)) => Self {
+                    args: url_args.collect(),
+                    url: true,
+                },
+                Ok(WebSource::Args(args)) => Self {
+                    args: args.into_iter().map(OsString::from).collect(),
+                    url: false,
+                },
+                Err(_) => Self {
+                    args: Vec::new(),
+                    url: false,
+                },
+            }
+        }
+    }
+
+    /// Snapshot the arguments of a url, on native or the web.
+    #[must_use]
+    pub fn from_url(url: &str) -> Self {
+        Self {
+            args: crate::source::from_url(url).collect(),
+            url: true,
+        }
+    }
+
+    /// Take a new snapshot, like [`new`](WebArgs::new).
+    pub fn refresh(&mut self) {
+        *self = Self::new();
+    }
+
+    /// # Parse the snapshot against a command
+    ///
+    /// Does not print or exit, like [`clap::Command::try_get_matches_from`].
+    ///
+    /// # Errors
+    ///
+    /// The clap error when the args don't parse, including help and version
+    /// requests.  This is normal clap behaviour.
+    pub fn reparse(&self, command: Command) -> error::Result<ArgMatches> {
+        if self.url {
+            let args = crate::translate::url_to_args(&command, self.args.iter().cloned())
+                .map_err(|err| err.into_clap(&command))?;
+            command.try_get_matches_from(args)
+        } else {
+            command.try_get_matches_from(&self.args)
+        }
+    }
+}
+
+impl Default for WebArgs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+
+    #[test]
+    fn reparse_changed_command() {
+        let args = WebArgs::from_url("/?point=1&point=2&name=bob");
+
+        let single = Command::new("app")
+            .arg(
+                Arg::new("point")
+                    .long("point")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(Arg::new("name").long("name"));
+        let matches = args.reparse(single).unwrap();
+        assert_eq!(matches.get_many::<String>("point").unwrap().len(), 2);
+
+        let pair = Command::new("app")
+            .arg(Arg::new("point").long("point").num_args(2))
+            .arg(Arg::new("name").long("name"));
+        let matches = args.reparse(pair).unwrap();
+        assert_eq!(
+            matches.get_occurrences::<String>("point").unwrap().count(),
+            1
+        );
+
+        let renamed = Command::new("app").arg(Arg::new("title").long("title"));
+        assert!(args.reparse(renamed).is_err());
+    }
+}
@@ -0,0 +1,38 @@
+//! `#[derive(WeParser)]`, with the "derive" feature.
+#![cfg(feature = "derive")]
+
+use clap::Parser;
+use we_clap::WeParser;
+
+#[derive(Parser, WeParser, Debug, PartialEq)]
+#[command(name = "app")]
+struct Opts {
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+#[derive(Parser, WeParser, Debug)]
+struct Generic<T>
+where
+    T: clap::Args,
+{
+    #[command(flatten)]
+    inner: T,
+}
+
+#[derive(clap::Args, Debug)]
+struct Inner {
+    #[arg(long)]
+    name: String,
+}
+
+#[test]
+fn derived_impl() {
+    let opts: Opts = Opts::we_try_parse_from::<Opts, _, _>(["app", "-v"]).unwrap();
+    assert_eq!(opts, Opts { verbose: true });
+
+    let generic: Generic<Inner> =
+        Generic::<Inner>::we_try_parse_from::<Generic<Inner>, _, _>(["app", "--name", "x"])
+            .unwrap();
+    assert_eq!(generic.inner.name, "x");
+}
@@ -0,0 +1,27 @@
+//! The query translation core builds without `std`.
+
+#![no_std]
+
+extern crate alloc;
+
+#[path = "../src/query.rs"]
+mod query;
+
+use query::{we_translate_queries, QueryRules};
+
+#[test]
+fn translate_without_std() {
+    let rules = QueryRules {
+        positional_delimiter: Some('|'),
+        ..QueryRules::default()
+    };
+    let args = we_translate_queries(["offset=-5", "n=bob", "_=a|b", "--verbose"], &rules);
+    assert_eq!(args, ["--offset=-5", "-n=bob", "a", "b", "--verbose"]);
+
+    let rules = QueryRules {
+        use_equals_form: false,
+        ..QueryRules::default()
+    };
+    let args = we_translate_queries(["name=-bob"], &rules);
+    assert_eq!(args, ["--name", "-bob"]);
+}
@@ -0,0 +1,121 @@
+//! Native and web parity.
+//!
+//! Each scenario is a native command line and the url that should mean the
+//! same thing on the web.  Both are parsed, the native one with
+//! [`WeParser::we_try_parse_from`] and the url through the web translation
+//! with [`WebArgs`], and must give the same result: equal `Opts`, or errors
+//! of the same kind.
+//!
+//! To add a scenario put a new `(argv, url)` line in the `SCENARIOS` of the
+//! test it belongs to, or add a field to `Opts` and a test of its own.
+
+use clap::error::ErrorKind;
+use clap::{ArgAction, CommandFactory, FromArgMatches, Parser, Subcommand};
+use we_clap::{WeParser, WebArgs};
+
+#[derive(Parser, Debug, PartialEq)]
+#[command(name = "app", version)]
+struct Opts {
+    #[arg(short, long)]
+    verbose: bool,
+    #[arg(short, action = ArgAction::Count)]
+    level: u8,
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    offset: i32,
+    #[arg(long)]
+    tag: Vec<String>,
+    #[arg(long, num_args = 2)]
+    point: Option<Vec<i32>>,
+    file: Option<String>,
+    #[command(subcommand)]
+    run: Option<Run>,
+}
+
+impl WeParser for Opts {}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum Run {
+    Fast {
+        #[arg(long)]
+        speed: u32,
+    },
+}
+
+/// Parse a native command line and a url, and assert the same outcome.
+fn assert_parity(argv: &str, url: &str) {
+    let native =
+        Opts::we_try_parse_from::<Opts, _, _>(argv.split_whitespace()).map_err(|err| err.kind());
+    let web = WebArgs::from_url(url)
+        .reparse(Opts::command())
+        .and_then(|matches| Opts::from_arg_matches(&matches))
+        .map_err(|err| err.kind());
+    assert_eq!(native, web, "native `{argv}` and web `{url}` differ");
+}
+
+#[test]
+fn flags() {
+    const SCENARIOS: &[(&str, &str)] = &[
+        ("app", "/"),
+        ("app --verbose", "/?--verbose"),
+        ("app --verbose", "/?verbose"),
+        ("app --verbose", "/?verbose="),
+        ("app -v --tag x", "/?v&tag=x"),
+        ("app -v", "/?-v"),
+        ("app -l -l -l", "/?-l&-l&-l"),
+    ];
+    for (argv, url) in SCENARIOS {
+        assert_parity(argv, url);
+    }
+}
+
+#[test]
+fn options() {
+    const SCENARIOS: &[(&str, &str)] = &[
+        ("app --offset=3", "/?offset=3"),
+        ("app --offset -5", "/?offset=-5"),
+        ("app in.txt", "/?_=in.txt"),
+        ("app fast --speed 9", "/?fast&speed=9"),
+    ];
+    for (argv, url) in SCENARIOS {
+        assert_parity(argv, url);
+    }
+}
+
+#[test]
+fn multiple_values() {
+    const SCENARIOS: &[(&str, &str)] = &[
+        ("app --tag a --tag b", "/?tag=a&tag=b"),
+        ("app --point 1 2", "/?point=1&point=2"),
+    ];
+    for (argv, url) in SCENARIOS {
+        assert_parity(argv, url);
+    }
+}
+
+#[test]
+fn errors() {
+    const SCENARIOS: &[(&str, &str)] = &[
+        ("app --offset=many", "/?offset=many"),
+        ("app --colour", "/?colour=red"),
+        ("app --point 1", "/?point=1"),
+        ("app fast", "/?fast"),
+    ];
+    for (argv, url) in SCENARIOS {
+        assert_parity(argv, url);
+    }
+}
+
+#[test]
+fn help_and_version() {
+    const SCENARIOS: &[(&str, &str)] = &[
+        ("app --help", "/?--help"),
+        ("app -h", "/?-h"),
+        ("app --version", "/?--version"),
+        ("app help", "/?help"),
+    ];
+    for (argv, url) in SCENARIOS {
+        assert_parity(argv, url);
+    }
+    let help = WebArgs::from_url("/?-h").reparse(Opts::command());
+    assert_eq!(help.unwrap_err().kind(), ErrorKind::DisplayHelp);
+}
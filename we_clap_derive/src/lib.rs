@@ -0,0 +1,24 @@
+#![warn(missing_docs)]
+//! # Derive macro for we_clap
+//!
+//! Use it through the "derive" feature of we_clap, not directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+/// # Derive `we_clap::WeParser`
+///
+/// Generates the empty `impl we_clap::WeParser for T {}`, nothing else,
+/// next to clap's own `#[derive(Parser)]`.  Generic types keep their
+/// generics and bounds.
+#[proc_macro_derive(WeParser)]
+pub fn derive_we_parser(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::we_clap::WeParser for #name #ty_generics #where_clause {}
+    }
+    .into()
+}